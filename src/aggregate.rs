@@ -0,0 +1,384 @@
+#![allow(unused_imports)]
+use chrono::{DateTime, FixedOffset, SecondsFormat, TimeDelta};
+use chrono_tz::Tz;
+use rhai::packages::{BasicArrayPackage, Package};
+use rhai::plugin::*;
+use rhai::{Array, Locked, Map, Scope, Shared, AST};
+use std::collections::BTreeMap;
+
+use crate::calendar::BucketStep;
+use crate::timedelta::{from_total_nanos, total_nanos};
+
+/// Contents of an array holding nothing but DateTime or nothing but TimeDelta instances
+pub enum Values {
+    DateTimes(Vec<DateTime<FixedOffset>>),
+    Deltas(Vec<TimeDelta>),
+}
+
+impl Values {
+    /// Read an array, which must not mix DateTime and TimeDelta instances or hold anything else
+    pub fn from_array(array: &Array) -> Result<Self, Box<EvalAltResult>> {
+        use crate::datetime::datetime_module::DateTimeFixed;
+        use crate::timedelta::timedelta_module::Timedelta;
+
+        let error = || Box::<EvalAltResult>::from("Array must only contain datetimes or only timedeltas".to_string());
+
+        if array.first().is_some_and(|item| item.is::<Timedelta>()) {
+            array
+                .iter()
+                .map(|item| {
+                    item.clone()
+                        .try_cast::<Timedelta>()
+                        .map(|td| *crate::timedelta::borrow_mut(&td))
+                        .ok_or_else(error)
+                })
+                .collect::<Result<_, _>>()
+                .map(Values::Deltas)
+        } else {
+            array
+                .iter()
+                .map(|item| {
+                    item.clone()
+                        .try_cast::<DateTimeFixed>()
+                        .map(|dt| *crate::datetime::borrow_mut(&dt))
+                        .ok_or_else(error)
+                })
+                .collect::<Result<_, _>>()
+                .map(Values::DateTimes)
+        }
+    }
+
+    /// DateTime instances of an array, which must not hold anything else
+    pub fn datetimes(array: &Array) -> Result<Vec<DateTime<FixedOffset>>, Box<EvalAltResult>> {
+        match Values::from_array(array)? {
+            Values::DateTimes(values) => Ok(values),
+            Values::Deltas(_) => Err(Box::<EvalAltResult>::from("Array must only contain datetimes".to_string())),
+        }
+    }
+
+    /// TimeDelta instances of an array as total nanoseconds, which must not hold anything else
+    pub fn deltas(array: &Array) -> Result<Vec<i128>, Box<EvalAltResult>> {
+        match Values::from_array(array)? {
+            Values::Deltas(values) => Ok(values.iter().map(total_nanos).collect()),
+            Values::DateTimes(values) if values.is_empty() => Ok(Vec::new()),
+            Values::DateTimes(_) => Err(Box::<EvalAltResult>::from("Array must only contain timedeltas".to_string())),
+        }
+    }
+}
+
+/// Precomputed sort key of a DateTime or TimeDelta instance
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    DateTime(DateTime<FixedOffset>),
+    Delta(TimeDelta),
+}
+
+impl SortKey {
+    fn of(value: &Dynamic) -> Option<Self> {
+        use crate::datetime::datetime_module::DateTimeFixed;
+        use crate::timedelta::timedelta_module::Timedelta;
+
+        if let Some(dt) = value.clone().try_cast::<DateTimeFixed>() {
+            return Some(SortKey::DateTime(*crate::datetime::borrow_mut(&dt)));
+        }
+
+        value
+            .clone()
+            .try_cast::<Timedelta>()
+            .map(|td| SortKey::Delta(*crate::timedelta::borrow_mut(&td)))
+    }
+}
+
+thread_local! {
+    /// Engine holding nothing but rhai's array functions, with scripts calling its own `sort` and `sort_desc`
+    static BUILTIN_SORT: (Engine, AST, AST) = {
+        let mut engine = Engine::new_raw();
+        engine.register_global_module(BasicArrayPackage::new().as_shared_module());
+
+        let ascending = engine.compile("array.sort()").unwrap();
+        let descending = engine.compile("array.sort_desc()").unwrap();
+
+        (engine, ascending, descending)
+    };
+}
+
+/// Sort an array with rhai's own `sort` or `sort_desc`, which the overloads registered by this crate shadow
+fn builtin_sort(array: &mut Array, descending: bool) -> Result<(), Box<EvalAltResult>> {
+    BUILTIN_SORT.with(|(engine, ascending_ast, descending_ast)| {
+        let mut scope = Scope::new();
+        scope.push("array", std::mem::take(array));
+
+        let result = engine.run_ast_with_scope(&mut scope, if descending { descending_ast } else { ascending_ast });
+
+        *array = scope.remove::<Array>("array").unwrap_or_default();
+
+        result.map_err(|mut e| {
+            e.set_position(Position::NONE);
+            e
+        })
+    })
+}
+
+/// Sort an array of DateTime or TimeDelta instances by precomputed keys
+///
+/// Arrays that don't start with a DateTime or TimeDelta are handed to rhai's own `sort`, keeping its types, order and errors.
+pub fn sort(array: &mut Array, descending: bool) -> Result<(), Box<EvalAltResult>> {
+    let keys = match array.first().and_then(SortKey::of) {
+        Some(_) => array.iter().map(SortKey::of).collect::<Option<Vec<_>>>(),
+        None => return builtin_sort(array, descending),
+    };
+
+    let type_id = array[0].type_id();
+
+    let keys = keys
+        .filter(|_| array.iter().all(|item| item.type_id() == type_id))
+        .ok_or_else(|| EvalAltResult::ErrorFunctionNotFound("elements of different types cannot be sorted".into(), Position::NONE))?;
+
+    let mut keyed: Vec<(SortKey, Dynamic)> = keys.into_iter().zip(array.drain(..)).collect();
+
+    if descending {
+        keyed.sort_by(|(a, _), (b, _)| b.cmp(a));
+    } else {
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    array.extend(keyed.into_iter().map(|(_, item)| item));
+
+    Ok(())
+}
+
+/// Value at percentile `rank / 100` of sorted nanoseconds, interpolating linearly between the closest ranks
+///
+/// The rank is given as a fraction `numerator / denominator` of the last index, so both integer and float percentiles stay exact.
+pub fn percentile(sorted: &[i128], numerator: i128, denominator: i128) -> Option<TimeDelta> {
+    let last = sorted.len().checked_sub(1)? as i128;
+    let position = last * numerator;
+    let lower = (position / denominator) as usize;
+    let rest = position % denominator;
+
+    let low = sorted[lower];
+    let high = sorted.get(lower + 1).copied().unwrap_or(low);
+
+    from_total_nanos(low + (high - low) * rest / denominator)
+}
+
+/// Group DateTime instances, or maps holding one under `key`, by the start of their bucket
+///
+/// Groups are keyed by the bucket start as RFC 3339 text in UTC, so the keys of the returned map sort chronologically and the
+/// same start reached through different offsets forms a single group. Fractional seconds are only written, at a fixed width,
+/// when some bucket starts within a second.
+pub fn group_by_bucket(array: &Array, step: &BucketStep, key: &str, timezone: Option<Tz>) -> Result<Map, Box<EvalAltResult>> {
+    use crate::datetime::datetime_module::DateTimeFixed;
+
+    let mut groups: BTreeMap<DateTime<FixedOffset>, Array> = BTreeMap::new();
+
+    for item in array {
+        let dt = match item.clone().try_cast::<Map>() {
+            Some(map) => map.get(key).and_then(|value| value.clone().try_cast::<DateTimeFixed>()),
+            None => item.clone().try_cast::<DateTimeFixed>(),
+        }
+        .ok_or(Box::<EvalAltResult>::from(format!(
+            "Array must only contain datetimes or maps holding a datetime under {}",
+            key
+        )))?;
+
+        let start = crate::calendar::bucket(&crate::datetime::borrow_mut(&dt), step, None, timezone)?;
+
+        groups.entry(start).or_default().push(item.clone());
+    }
+
+    let format = if groups.keys().all(|start| start.timestamp_subsec_nanos() == 0) {
+        SecondsFormat::Secs
+    } else {
+        SecondsFormat::Nanos
+    };
+
+    Ok(groups
+        .into_iter()
+        .map(|(start, items)| (start.to_utc().to_rfc3339_opts(format, true).into(), items.into()))
+        .collect())
+}
+
+#[export_module]
+pub mod aggregate_module {
+    use chrono::DateTime;
+    use chrono::FixedOffset;
+    use chrono::TimeDelta;
+
+    use rhai::{Array, Dynamic, EvalAltResult, Locked, Map, Shared};
+
+    use crate::timedelta::timedelta_module::Timedelta;
+
+    /// Wrap a DateTime as a new rhai value
+    fn datetime(dt: Option<DateTime<FixedOffset>>) -> Dynamic {
+        dt.map_or(Dynamic::UNIT, |dt| Dynamic::from(Shared::new(Locked::new(dt))))
+    }
+
+    /// Wrap a TimeDelta as a new rhai value
+    fn timedelta(td: Option<TimeDelta>) -> Dynamic {
+        td.map_or(Dynamic::UNIT, |td| Dynamic::from(Shared::new(Locked::new(td))))
+    }
+
+    /// Earliest DateTime or shortest TimeDelta of an array, or unit if empty
+    #[rhai_fn(global, name = "min", pure, return_raw)]
+    pub fn min(array: &mut Array) -> Result<Dynamic, Box<EvalAltResult>> {
+        Ok(match Values::from_array(array)? {
+            Values::DateTimes(values) => datetime(values.into_iter().min()),
+            Values::Deltas(values) => timedelta(values.into_iter().min()),
+        })
+    }
+
+    /// Latest DateTime or longest TimeDelta of an array, or unit if empty
+    #[rhai_fn(global, name = "max", pure, return_raw)]
+    pub fn max(array: &mut Array) -> Result<Dynamic, Box<EvalAltResult>> {
+        Ok(match Values::from_array(array)? {
+            Values::DateTimes(values) => datetime(values.into_iter().max()),
+            Values::Deltas(values) => timedelta(values.into_iter().max()),
+        })
+    }
+
+    /// Sort an array in ascending order, ordering DateTime and TimeDelta instances and leaving anything else to rhai's own `sort`
+    #[rhai_fn(global, name = "sort", return_raw)]
+    pub fn sort(array: &mut Array) -> Result<(), Box<EvalAltResult>> {
+        super::sort(array, false)
+    }
+
+    /// Sort an array in descending order, ordering DateTime and TimeDelta instances and leaving anything else to rhai's own `sort_desc`
+    #[rhai_fn(global, name = "sort_desc", return_raw)]
+    pub fn sort_desc(array: &mut Array) -> Result<(), Box<EvalAltResult>> {
+        super::sort(array, true)
+    }
+
+    /// Earliest DateTime of an array, or unit if empty
+    #[rhai_fn(global, name = "earliest", pure, return_raw)]
+    pub fn earliest(array: &mut Array) -> Result<Dynamic, Box<EvalAltResult>> {
+        Ok(datetime(Values::datetimes(array)?.into_iter().min()))
+    }
+
+    /// Latest DateTime of an array, or unit if empty
+    #[rhai_fn(global, name = "latest", pure, return_raw)]
+    pub fn latest(array: &mut Array) -> Result<Dynamic, Box<EvalAltResult>> {
+        Ok(datetime(Values::datetimes(array)?.into_iter().max()))
+    }
+
+    /// Sum of the TimeDelta instances of an array, zero if empty
+    #[rhai_fn(global, name = "sum", pure, return_raw)]
+    pub fn sum(array: &mut Array) -> Result<Dynamic, Box<EvalAltResult>> {
+        let total: i128 = Values::deltas(array)?.iter().sum();
+
+        from_total_nanos(total)
+            .map(|td| timedelta(Some(td)))
+            .ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+    }
+
+    /// Mean of the TimeDelta instances of an array truncated to the nanosecond, or unit if empty
+    #[rhai_fn(global, name = "mean", pure, return_raw)]
+    pub fn mean(array: &mut Array) -> Result<Dynamic, Box<EvalAltResult>> {
+        let values = Values::deltas(array)?;
+
+        if values.is_empty() {
+            return Ok(Dynamic::UNIT);
+        }
+
+        Ok(timedelta(from_total_nanos(values.iter().sum::<i128>() / values.len() as i128)))
+    }
+
+    /// Median of the TimeDelta instances of an array, or unit if empty
+    #[rhai_fn(global, name = "median", pure, return_raw)]
+    pub fn median(array: &mut Array) -> Result<Dynamic, Box<EvalAltResult>> {
+        let mut values = Values::deltas(array)?;
+        values.sort();
+
+        Ok(timedelta(super::percentile(&values, 1, 2)))
+    }
+
+    /// Percentile from 0 to 100 of the TimeDelta instances of an array, or unit if empty
+    #[rhai_fn(global, name = "percentile", pure, return_raw)]
+    pub fn percentile(array: &mut Array, p: rhai::INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        if !(0..=100).contains(&p) {
+            return Err(Box::<EvalAltResult>::from("Percentile must be between 0 and 100".to_string()));
+        }
+
+        let mut values = Values::deltas(array)?;
+        values.sort();
+
+        Ok(timedelta(super::percentile(&values, p as i128, 100)))
+    }
+
+    /// Fractional percentile from 0 to 100 of the TimeDelta instances of an array, or unit if empty
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "percentile", pure, return_raw)]
+    pub fn percentile_float(array: &mut Array, p: rhai::FLOAT) -> Result<Dynamic, Box<EvalAltResult>> {
+        if !(0.0..=100.0).contains(&p) {
+            return Err(Box::<EvalAltResult>::from("Percentile must be between 0 and 100".to_string()));
+        }
+
+        let mut values = Values::deltas(array)?;
+        values.sort();
+
+        // exact to a billionth of a percent
+        Ok(timedelta(super::percentile(&values, (p * 1e9).round() as i128, 100_000_000_000)))
+    }
+
+    /// Population standard deviation of the TimeDelta instances of an array rounded to the nanosecond, or unit if empty
+    #[rhai_fn(global, name = "stddev", pure, return_raw)]
+    pub fn stddev(array: &mut Array) -> Result<Dynamic, Box<EvalAltResult>> {
+        let values = Values::deltas(array)?;
+
+        if values.is_empty() {
+            return Ok(Dynamic::UNIT);
+        }
+
+        let count = values.len() as f64;
+        let mean = values.iter().map(|nanos| *nanos as f64).sum::<f64>() / count;
+        let variance = values.iter().map(|nanos| (*nanos as f64 - mean).powi(2)).sum::<f64>() / count;
+
+        Ok(timedelta(from_total_nanos(variance.sqrt().round() as i128)))
+    }
+
+    /// Group an array of DateTime instances by the start of their fixed length bucket
+    #[rhai_fn(global, name = "group_by_bucket", pure, return_raw)]
+    pub fn group_by_bucket(array: &mut Array, step: Timedelta) -> Result<Map, Box<EvalAltResult>> {
+        group_by_bucket_key(array, step, "")
+    }
+
+    /// Group an array of DateTime instances by the start of their calendar or fixed length bucket, such as "day" or "15m"
+    #[rhai_fn(global, name = "group_by_bucket", pure, return_raw)]
+    pub fn group_by_bucket_by(array: &mut Array, step: &str) -> Result<Map, Box<EvalAltResult>> {
+        group_by_bucket_by_key(array, step, "")
+    }
+
+    /// Group an array of DateTime instances, or maps holding one under `key`, by the start of their fixed length bucket
+    #[rhai_fn(global, name = "group_by_bucket", pure, return_raw)]
+    pub fn group_by_bucket_key(array: &mut Array, step: Timedelta, key: &str) -> Result<Map, Box<EvalAltResult>> {
+        let step = BucketStep::from_delta(&crate::timedelta::borrow_mut(&step))?;
+
+        super::group_by_bucket(array, &step, key, None)
+    }
+
+    /// Group an array of DateTime instances, or maps holding one under `key`, by the start of their calendar or fixed length bucket
+    #[rhai_fn(global, name = "group_by_bucket", pure, return_raw)]
+    pub fn group_by_bucket_by_key(array: &mut Array, step: &str, key: &str) -> Result<Map, Box<EvalAltResult>> {
+        super::group_by_bucket(array, &BucketStep::parse(step)?, key, None)
+    }
+
+    /// Group an array of DateTime instances, or maps holding one under `key`, by the start of their fixed length bucket in an IANA timezone
+    #[rhai_fn(global, name = "group_by_bucket", pure, return_raw)]
+    pub fn group_by_bucket_key_in(array: &mut Array, step: Timedelta, key: &str, timezone: &str) -> Result<Map, Box<EvalAltResult>> {
+        let step = BucketStep::from_delta(&crate::timedelta::borrow_mut(&step))?;
+
+        super::group_by_bucket(array, &step, key, Some(crate::calendar::parse_timezone(timezone)?))
+    }
+
+    /// Group an array of DateTime instances, or maps holding one under `key`, by the start of their calendar or fixed length bucket
+    /// following the wall clock of an IANA timezone
+    #[rhai_fn(global, name = "group_by_bucket", pure, return_raw)]
+    pub fn group_by_bucket_by_key_in(array: &mut Array, step: &str, key: &str, timezone: &str) -> Result<Map, Box<EvalAltResult>> {
+        super::group_by_bucket(
+            array,
+            &BucketStep::parse(step)?,
+            key,
+            Some(crate::calendar::parse_timezone(timezone)?),
+        )
+    }
+}
@@ -0,0 +1,489 @@
+#![allow(unused_imports)]
+use chrono::{
+    DateTime, Datelike, Days, FixedOffset, Locale, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Timelike, Weekday,
+};
+use chrono_tz::Tz;
+use rhai::plugin::*;
+use rhai::{Array, Dynamic, EvalAltResult, Locked, Map, Shared};
+use std::str::FromStr;
+
+/// Parse week start day name such as "mon" or "Sunday"
+pub fn parse_weekday(weekday: &str) -> Result<Weekday, Box<EvalAltResult>> {
+    Weekday::from_str(weekday).map_err(|_e| Box::<EvalAltResult>::from(format!("Invalid weekday provided: {}", weekday)))
+}
+
+/// Parse locale name such as "fr_FR"
+pub fn parse_locale(locale: &str) -> Result<Locale, Box<EvalAltResult>> {
+    Locale::from_str(locale).map_err(|_e| Box::<EvalAltResult>::from(format!("Invalid locale provided: {}", locale)))
+}
+
+/// Check month is within 1-12
+pub fn check_month(month: rhai::INT) -> Result<u32, Box<EvalAltResult>> {
+    if (1..=12).contains(&month) {
+        Ok(month as u32)
+    } else {
+        Err(Box::<EvalAltResult>::from(
+            "Month out of range or doesn't make any sense.".to_string(),
+        ))
+    }
+}
+
+/// Check year is within chrono's supported range
+pub fn check_year(year: rhai::INT) -> Result<i32, Box<EvalAltResult>> {
+    i32::try_from(year)
+        .ok()
+        .filter(|y| NaiveDate::from_ymd_opt(*y, 1, 1).is_some())
+        .ok_or(Box::<EvalAltResult>::from(
+            "Year out of range or doesn't make any sense.".to_string(),
+        ))
+}
+
+/// Check if the year is a leap year in the proleptic Gregorian calendar
+pub fn is_leap_year(year: rhai::INT) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Number of days in the given month
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year as rhai::INT) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Error for DateTime arithmetic leaving chrono's range
+pub fn out_of_range() -> Box<EvalAltResult> {
+    Box::<EvalAltResult>::from("DateTime out of range or doesn't make any sense.".to_string())
+}
+
+/// Place a local wall clock time in the DateTime's own offset
+pub fn with_local(dt: &DateTime<FixedOffset>, local: NaiveDateTime) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+    local.and_local_timezone(dt.timezone()).single().ok_or_else(out_of_range)
+}
+
+/// Truncate a wall clock time to the start of the given unit
+fn start_of_local(local: NaiveDateTime, unit: &str) -> Result<NaiveDateTime, Box<EvalAltResult>> {
+    let date = local.date();
+
+    match unit.to_lowercase().as_str() {
+        "year" => date.with_ordinal(1).map(|d| d.and_time(NaiveTime::MIN)),
+        "half" => NaiveDate::from_ymd_opt(date.year(), date.month0() / 6 * 6 + 1, 1).map(|d| d.and_time(NaiveTime::MIN)),
+        "quarter" => NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1).map(|d| d.and_time(NaiveTime::MIN)),
+        "month" => date.with_day(1).map(|d| d.and_time(NaiveTime::MIN)),
+        "week" => date
+            .checked_sub_days(Days::new(date.weekday().num_days_from_monday() as u64))
+            .map(|d| d.and_time(NaiveTime::MIN)),
+        "day" => Some(date.and_time(NaiveTime::MIN)),
+        "hour" => local
+            .with_nanosecond(0)
+            .and_then(|l| l.with_second(0))
+            .and_then(|l| l.with_minute(0)),
+        "minute" => local.with_nanosecond(0).and_then(|l| l.with_second(0)),
+        "second" => local.with_nanosecond(0),
+        _ => {
+            return Err(Box::<EvalAltResult>::from(format!(
+                "Invalid unit provided: {}. Supported units are year, half, quarter, month, week, day, hour, minute and second",
+                unit
+            )))
+        }
+    }
+    .ok_or_else(out_of_range)
+}
+
+/// Truncate DateTime to the start of the given unit in its own offset
+pub fn start_of(dt: &DateTime<FixedOffset>, unit: &str) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+    with_local(dt, start_of_local(dt.naive_local(), unit)?)
+}
+
+/// Last nanosecond of the given unit in the DateTime's own offset
+pub fn end_of(dt: &DateTime<FixedOffset>, unit: &str) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+    let start = start_of(dt, unit)?;
+
+    let next = match unit.to_lowercase().as_str() {
+        "year" => start.checked_add_months(Months::new(12)),
+        "half" => start.checked_add_months(Months::new(6)),
+        "quarter" => start.checked_add_months(Months::new(3)),
+        "month" => start.checked_add_months(Months::new(1)),
+        "week" => start.checked_add_days(Days::new(7)),
+        "day" => start.checked_add_days(Days::new(1)),
+        "hour" => start.checked_add_signed(TimeDelta::hours(1)),
+        "minute" => start.checked_add_signed(TimeDelta::minutes(1)),
+        _ => start.checked_add_signed(TimeDelta::seconds(1)),
+    };
+
+    next.and_then(|n| n.checked_sub_signed(TimeDelta::nanoseconds(1)))
+        .ok_or_else(out_of_range)
+}
+
+/// Parse IANA timezone name such as "Europe/Berlin"
+pub fn parse_timezone(timezone: &str) -> Result<Tz, Box<EvalAltResult>> {
+    timezone
+        .parse::<Tz>()
+        .map_err(|_e| Box::<EvalAltResult>::from(format!("Invalid IANA timezone provided: {}", timezone)))
+}
+
+/// Place a wall clock time in an IANA timezone, taking the earlier instant when clocks fall back and an hour later when the
+/// time falls into the gap of clocks springing forward
+fn with_zone(tz: Tz, local: NaiveDateTime) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(local + TimeDelta::hours(1))).earliest())
+        .map(|dt| dt.fixed_offset())
+        .ok_or_else(out_of_range)
+}
+
+/// Width of a time-series bucket
+pub enum BucketStep {
+    /// Fixed length in nanoseconds, counted from an origin
+    Fixed(i128),
+    /// Calendar unit following the local wall clock, such as "day" or "month"
+    Calendar(String),
+}
+
+impl BucketStep {
+    /// Fixed length step, which must be positive
+    pub fn from_delta(td: &TimeDelta) -> Result<Self, Box<EvalAltResult>> {
+        if *td <= TimeDelta::zero() {
+            return Err(Box::<EvalAltResult>::from("Bucket step must be positive".to_string()));
+        }
+
+        Ok(BucketStep::Fixed(crate::timedelta::total_nanos(td)))
+    }
+
+    /// Calendar unit such as "day", "week" (Monday), "month", "quarter", "half" or "year", or a fixed length such as "15m"
+    pub fn parse(step: &str) -> Result<Self, Box<EvalAltResult>> {
+        match step.trim().to_lowercase().as_str() {
+            unit @ ("day" | "week" | "month" | "quarter" | "half" | "year") => Ok(BucketStep::Calendar(unit.to_string())),
+            _ => Self::from_delta(&crate::timedelta::parse_human(step)?),
+        }
+    }
+}
+
+/// Start of the time-series bucket holding the DateTime
+///
+/// Fixed steps count from `origin`, by default midnight of 1970-01-01 in the DateTime's offset so hourly buckets follow the
+/// local clock. Calendar steps follow the wall clock of `timezone`, or of the DateTime's own offset without one.
+pub fn bucket(
+    dt: &DateTime<FixedOffset>,
+    step: &BucketStep,
+    origin: Option<DateTime<FixedOffset>>,
+    timezone: Option<Tz>,
+) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+    let dt = timezone.map_or(*dt, |tz| dt.with_timezone(&tz).fixed_offset());
+
+    match step {
+        BucketStep::Fixed(step) => {
+            let origin = match origin {
+                Some(origin) => origin,
+                None => with_local(&dt, DateTime::UNIX_EPOCH.naive_utc())?,
+            };
+
+            let since = crate::timedelta::total_nanos(&dt.signed_duration_since(origin));
+            let start = crate::timedelta::from_total_nanos(since.div_euclid(*step) * step)
+                .and_then(|td| origin.checked_add_signed(td))
+                .ok_or_else(out_of_range)?;
+
+            Ok(match timezone {
+                Some(tz) => start.with_timezone(&tz).fixed_offset(),
+                None => start.with_timezone(&dt.timezone()),
+            })
+        }
+        BucketStep::Calendar(_) if origin.is_some() => Err(Box::<EvalAltResult>::from(
+            "An origin only applies to fixed length steps, calendar steps start with the unit".to_string(),
+        )),
+        BucketStep::Calendar(unit) => match timezone {
+            Some(tz) => with_zone(tz, start_of_local(dt.naive_local(), unit)?),
+            None => start_of(&dt, unit),
+        },
+    }
+}
+
+/// Calendar year in which the fiscal year containing the date starts
+fn fiscal_start_year(date: NaiveDate, start_month: u32) -> i32 {
+    if date.month() >= start_month {
+        date.year()
+    } else {
+        date.year() - 1
+    }
+}
+
+/// Fiscal year label, being the calendar year in which the fiscal year ends
+pub fn fiscal_year(dt: &DateTime<FixedOffset>, start_month: u32) -> i32 {
+    let start_year = fiscal_start_year(dt.date_naive(), start_month);
+
+    if start_month == 1 {
+        start_year
+    } else {
+        start_year + 1
+    }
+}
+
+/// Fiscal quarter 1-4
+pub fn fiscal_quarter(dt: &DateTime<FixedOffset>, start_month: u32) -> u32 {
+    (dt.month() + 12 - start_month) % 12 / 3 + 1
+}
+
+/// Midnight of the first day of the fiscal year containing the DateTime
+pub fn fiscal_year_start(dt: &DateTime<FixedOffset>, start_month: u32) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+    let start = NaiveDate::from_ymd_opt(fiscal_start_year(dt.date_naive(), start_month), start_month, 1).ok_or_else(out_of_range)?;
+
+    with_local(dt, start.and_time(NaiveTime::MIN))
+}
+
+/// Last nanosecond of the fiscal year containing the DateTime
+pub fn fiscal_year_end(dt: &DateTime<FixedOffset>, start_month: u32) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+    fiscal_year_start(dt, start_month)?
+        .checked_add_months(Months::new(12))
+        .and_then(|n| n.checked_sub_signed(TimeDelta::nanoseconds(1)))
+        .ok_or_else(out_of_range)
+}
+
+/// Last day of a 52-53 week fiscal year ending in `end_month` of `year`
+///
+/// The year ends either on the last `end_weekday` of the month or on the `end_weekday` nearest to the end of the month.
+pub fn retail_year_end(year: i32, end_month: u32, end_weekday: Weekday, nearest: bool) -> Option<NaiveDate> {
+    let month_end = NaiveDate::from_ymd_opt(year, end_month, 1)?
+        .checked_add_months(Months::new(1))?
+        .pred_opt()?;
+    let last = month_end.checked_sub_days(Days::new(month_end.weekday().days_since(end_weekday) as u64))?;
+
+    if nearest && month_end.signed_duration_since(last).num_days() > 3 {
+        last.checked_add_days(Days::new(7))
+    } else {
+        Some(last)
+    }
+}
+
+/// Position of the DateTime within a 52-53 week retail calendar such as 4-4-5
+pub fn retail_period(
+    dt: &DateTime<FixedOffset>,
+    end_month: u32,
+    end_weekday: Weekday,
+    nearest: bool,
+    pattern: &str,
+) -> Result<Map, Box<EvalAltResult>> {
+    let weeks_per_period: Vec<i64> = match pattern {
+        "445" | "4-4-5" => vec![4, 4, 5],
+        "454" | "4-5-4" => vec![4, 5, 4],
+        "544" | "5-4-4" => vec![5, 4, 4],
+        _ => {
+            return Err(Box::<EvalAltResult>::from(format!(
+                "Invalid pattern provided: {}. Supported patterns are 445, 454 and 544",
+                pattern
+            )))
+        }
+    };
+
+    let date = dt.date_naive();
+    let year_end = |year: i32| retail_year_end(year, end_month, end_weekday, nearest).ok_or_else(out_of_range);
+
+    let mut year = date.year();
+
+    while date > year_end(year)? {
+        year += 1;
+    }
+
+    while date <= year_end(year - 1)? {
+        year -= 1;
+    }
+
+    let last_day = year_end(year)?;
+    let first_day = year_end(year - 1)?.succ_opt().ok_or_else(out_of_range)?;
+
+    let week = date.signed_duration_since(first_day).num_days() / 7 + 1;
+    let weeks_in_year = (last_day.signed_duration_since(first_day).num_days() + 1) / 7;
+
+    let mut period = 1;
+    let mut weeks_until = 0;
+
+    for weeks in weeks_per_period.iter().cycle().take(11) {
+        weeks_until += weeks;
+
+        if week <= weeks_until {
+            break;
+        }
+
+        period += 1;
+    }
+
+    let mut map = Map::new();
+
+    map.insert("fiscal_year".into(), Dynamic::from_int(year as rhai::INT));
+    map.insert("quarter".into(), Dynamic::from_int(((period - 1) / 3 + 1) as rhai::INT));
+    map.insert("period".into(), Dynamic::from_int(period as rhai::INT));
+    map.insert("week".into(), Dynamic::from_int(week as rhai::INT));
+    map.insert("weeks_in_year".into(), Dynamic::from_int(weeks_in_year as rhai::INT));
+    map.insert(
+        "year_start".into(),
+        Dynamic::from(Shared::new(Locked::new(with_local(dt, first_day.and_time(NaiveTime::MIN))?))),
+    );
+    map.insert(
+        "year_end".into(),
+        Dynamic::from(Shared::new(Locked::new(
+            with_local(dt, last_day.and_time(NaiveTime::MIN))?
+                .checked_add_days(Days::new(1))
+                .and_then(|n| n.checked_sub_signed(TimeDelta::nanoseconds(1)))
+                .ok_or_else(out_of_range)?,
+        ))),
+    );
+
+    Ok(map)
+}
+
+/// Build the week rows of a month, padded with days of the neighbouring months
+pub fn month_grid(year: i32, month: u32, week_start: Weekday, locale: Locale) -> Result<Array, Box<EvalAltResult>> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).ok_or(Box::<EvalAltResult>::from(
+        "Month out of range or doesn't make any sense.".to_string(),
+    ))?;
+
+    let leading = first.weekday().days_since(week_start) as u64;
+    let mut day = first
+        .checked_sub_days(chrono::Days::new(leading))
+        .ok_or(Box::<EvalAltResult>::from("Date out of range".to_string()))?;
+
+    let mut weeks = Array::new();
+
+    loop {
+        let mut week = Array::with_capacity(7);
+
+        for _ in 0..7 {
+            week.push(Dynamic::from_map(day_entry(day, month, locale)));
+
+            day = day.succ_opt().ok_or(Box::<EvalAltResult>::from("Date out of range".to_string()))?;
+        }
+
+        weeks.push(Dynamic::from_array(week));
+
+        if day.month() != month || day.year() != year {
+            break;
+        }
+    }
+
+    Ok(weeks)
+}
+
+fn day_entry(date: NaiveDate, month: u32, locale: Locale) -> Map {
+    let mut entry = Map::new();
+
+    entry.insert(
+        "date".into(),
+        Dynamic::from(Shared::new(Locked::new(
+            date.and_time(chrono::NaiveTime::MIN).and_utc().fixed_offset(),
+        ))),
+    );
+    entry.insert("year".into(), Dynamic::from_int(date.year() as rhai::INT));
+    entry.insert("month".into(), Dynamic::from_int(date.month() as rhai::INT));
+    entry.insert("day".into(), Dynamic::from_int(date.day() as rhai::INT));
+    entry.insert("in_month".into(), Dynamic::from_bool(date.month() == month));
+    entry.insert(
+        "weekday".into(),
+        Dynamic::from_int(date.weekday().number_from_monday() as rhai::INT),
+    );
+    entry.insert(
+        "weekday_name".into(),
+        Dynamic::from(format!("{}", date.format_localized("%A", locale))),
+    );
+    entry.insert(
+        "month_name".into(),
+        Dynamic::from(format!("{}", date.format_localized("%B", locale))),
+    );
+    entry.insert("iso_week".into(), Dynamic::from_int(date.iso_week().week() as rhai::INT));
+
+    entry
+}
+
+#[export_module]
+pub mod calendar_module {
+    use chrono::Datelike;
+    use chrono::Locale;
+    use chrono::NaiveDate;
+    use chrono::Weekday;
+
+    use rhai::{Array, Dynamic, EvalAltResult, Position};
+
+    /// Check if the year is a leap year
+    #[rhai_fn(name = "is_leap_year")]
+    pub fn is_leap_year_of(year: rhai::INT) -> bool {
+        is_leap_year(year)
+    }
+
+    /// Number of days in the month of the year
+    #[rhai_fn(return_raw, name = "days_in_month")]
+    pub fn days_in_month_of(year: rhai::INT, month: rhai::INT) -> Result<rhai::INT, Box<EvalAltResult>> {
+        Ok(days_in_month(check_year(year)?, check_month(month)?) as rhai::INT)
+    }
+
+    /// Number of days in the year
+    #[rhai_fn(return_raw, name = "days_in_year")]
+    pub fn days_in_year_of(year: rhai::INT) -> Result<rhai::INT, Box<EvalAltResult>> {
+        Ok(if is_leap_year(check_year(year)? as rhai::INT) { 366 } else { 365 })
+    }
+
+    /// Number of ISO 8601 weeks in the ISO week-numbering year, either 52 or 53
+    #[rhai_fn(return_raw, name = "weeks_in_iso_year")]
+    pub fn weeks_in_iso_year(year: rhai::INT) -> Result<rhai::INT, Box<EvalAltResult>> {
+        // Dec 28th always falls into the last ISO week of its year
+        NaiveDate::from_ymd_opt(check_year(year)?, 12, 28)
+            .map(|d| d.iso_week().week() as rhai::INT)
+            .ok_or(Box::<EvalAltResult>::from(
+                "Year out of range or doesn't make any sense.".to_string(),
+            ))
+    }
+
+    /// Check if year, month and day make a valid date
+    #[rhai_fn(name = "is_valid_date")]
+    pub fn is_valid_date(year: rhai::INT, month: rhai::INT, day: rhai::INT) -> bool {
+        match (i32::try_from(year), u32::try_from(month), u32::try_from(day)) {
+            (Ok(year), Ok(month), Ok(day)) => NaiveDate::from_ymd_opt(year, month, day).is_some(),
+            _ => false,
+        }
+    }
+
+    /// Month grid as array of weeks, each an array of 7 day maps
+    #[rhai_fn(return_raw, name = "calendar_month")]
+    pub fn calendar_month(year: rhai::INT, month: rhai::INT, week_start: &str) -> Result<Array, Box<EvalAltResult>> {
+        month_grid(check_year(year)?, check_month(month)?, parse_weekday(week_start)?, Locale::POSIX)
+    }
+
+    /// Month grid as array of weeks, each an array of 7 day maps with localized names
+    #[rhai_fn(return_raw, name = "calendar_month")]
+    pub fn calendar_month_localized(
+        year: rhai::INT,
+        month: rhai::INT,
+        week_start: &str,
+        locale: &str,
+    ) -> Result<Array, Box<EvalAltResult>> {
+        month_grid(
+            check_year(year)?,
+            check_month(month)?,
+            parse_weekday(week_start)?,
+            parse_locale(locale)?,
+        )
+    }
+
+    /// Weekday names in display order starting from the given day
+    #[rhai_fn(return_raw, name = "calendar_weekdays")]
+    pub fn calendar_weekdays(week_start: &str) -> Result<Array, Box<EvalAltResult>> {
+        calendar_weekdays_localized(week_start, "POSIX")
+    }
+
+    /// Localized weekday names in display order starting from the given day
+    #[rhai_fn(return_raw, name = "calendar_weekdays")]
+    pub fn calendar_weekdays_localized(week_start: &str, locale: &str) -> Result<Array, Box<EvalAltResult>> {
+        let week_start = parse_weekday(week_start)?;
+        let locale = parse_locale(locale)?;
+
+        // 2024-01-01 is a Monday
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        Ok((0..7)
+            .map(|n| {
+                let day = monday + chrono::Days::new((week_start.num_days_from_monday() as u64 + n) % 7);
+
+                Dynamic::from(format!("{}", day.format_localized("%A", locale)))
+            })
+            .collect())
+    }
+}
@@ -33,6 +33,8 @@ pub mod datetime_module {
 
     use rhai::{EvalAltResult, Locked, Position, Shared};
 
+    use crate::calendar::BucketStep;
+    use crate::format::format_module::DateTimeFormat;
     use crate::timedelta::timedelta_module::Timedelta;
 
     /// Alias type to bridge rhai and chrono DateTime
@@ -80,6 +82,230 @@ pub mod datetime_module {
         Ok(Shared::new(Locked::new(DateTime::from_timestamp_nanos(nanos).fixed_offset())))
     }
 
+    /// Construct DateTime with UNIX timestamp in seconds, milliseconds, microseconds or nanoseconds, guessing the unit from its magnitude
+    #[rhai_fn(return_raw, name = "datetime_epoch")]
+    pub fn datetime_epoch(value: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_ticks(value, crate::epoch::unix_ticks_per_second(value), 0)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with fractional UNIX timestamp in seconds, rounded to the microsecond
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(return_raw, name = "datetime_epoch")]
+    pub fn datetime_epoch_float(secs: rhai::FLOAT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_seconds(secs, 0)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with Excel serial day number in the 1900 date system, valid from 0 (1899-12-31) to 9999-12-31
+    /// Serial 60 is Excel's non-existent Feb 29th 1900 and fails like any other out of range value.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(return_raw, name = "datetime_excel")]
+    pub fn datetime_excel(serial: rhai::FLOAT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        datetime_excel_system(serial, 1900)
+    }
+
+    /// Construct DateTime with whole Excel serial day number in the 1900 date system
+    #[rhai_fn(return_raw, name = "datetime_excel")]
+    pub fn datetime_excel_int(serial: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        datetime_excel_int_system(serial, 1900)
+    }
+
+    /// Construct DateTime with Excel serial day number in the 1900 or 1904 date system
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(return_raw, name = "datetime_excel")]
+    pub fn datetime_excel_system(serial: rhai::FLOAT, system: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_excel(serial, excel_system(system)?)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with whole Excel serial day number in the 1900 or 1904 date system
+    #[rhai_fn(return_raw, name = "datetime_excel")]
+    pub fn datetime_excel_int_system(serial: rhai::INT, system: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_excel_day(serial, excel_system(system)?)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with Windows FILETIME, the count of 100 nanosecond intervals since 1601-01-01 UTC
+    #[rhai_fn(return_raw)]
+    pub fn datetime_filetime(ticks: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        if ticks < 0 {
+            return Err(Box::<EvalAltResult>::from("Timestamp out of range".to_string()));
+        }
+
+        crate::epoch::from_ticks(ticks, 10_000_000, crate::epoch::FILETIME_EPOCH)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with .NET ticks, the count of 100 nanosecond intervals since 0001-01-01, valid up to 9999-12-31
+    #[rhai_fn(return_raw)]
+    pub fn datetime_dotnet_ticks(ticks: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        if !(0..=crate::epoch::DOTNET_MAX_TICKS).contains(&ticks) {
+            return Err(Box::<EvalAltResult>::from("Timestamp out of range".to_string()));
+        }
+
+        crate::epoch::from_ticks(ticks, 10_000_000, crate::epoch::DOTNET_EPOCH)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with NTP seconds since 1900-01-01 UTC
+    /// Seconds past era 0 (2036-02-07) keep counting instead of wrapping around.
+    #[rhai_fn(return_raw, name = "datetime_ntp")]
+    pub fn datetime_ntp(secs: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        datetime_ntp_fraction(secs, 0)
+    }
+
+    /// Construct DateTime with NTP seconds since 1900-01-01 UTC and 32-bit fraction of a second
+    #[rhai_fn(return_raw, name = "datetime_ntp")]
+    pub fn datetime_ntp_fraction(secs: rhai::INT, fraction: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        if !(0..=u32::MAX as rhai::INT).contains(&fraction) {
+            return Err(Box::<EvalAltResult>::from(
+                "NTP fraction must be between 0 and 4294967295".to_string(),
+            ));
+        }
+
+        let nanos = ((fraction as u64 * 1_000_000_000) >> 32) as u32;
+
+        secs.checked_add(crate::epoch::NTP_EPOCH)
+            .and_then(|secs| DateTime::from_timestamp(secs, nanos))
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt.fixed_offset())))
+    }
+
+    /// Construct DateTime with Apple Cocoa seconds since 2001-01-01 UTC, rounded to the microsecond
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(return_raw, name = "datetime_cocoa")]
+    pub fn datetime_cocoa(secs: rhai::FLOAT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_seconds(secs, crate::epoch::COCOA_EPOCH)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with whole Apple Cocoa seconds since 2001-01-01 UTC
+    #[rhai_fn(return_raw, name = "datetime_cocoa")]
+    pub fn datetime_cocoa_int(secs: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_ticks(secs, 1, crate::epoch::COCOA_EPOCH)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with fractional Julian Day number, rounded to the microsecond
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(return_raw, name = "datetime_julian_day")]
+    pub fn datetime_julian_day(julian_day: rhai::FLOAT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_julian_day(julian_day)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with whole Julian Day number, being noon UTC
+    #[rhai_fn(return_raw, name = "datetime_julian_day")]
+    pub fn datetime_julian_day_int(julian_day: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_julian_day_number(julian_day)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with fractional Modified Julian Day number, rounded to the microsecond
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(return_raw, name = "datetime_mjd")]
+    pub fn datetime_mjd(mjd: rhai::FLOAT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_modified_julian_day(mjd)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with whole Modified Julian Day number, being midnight UTC
+    #[rhai_fn(return_raw, name = "datetime_mjd")]
+    pub fn datetime_mjd_int(mjd: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::from_modified_julian_day_number(mjd)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with the millisecond timestamp of a ULID
+    #[rhai_fn(return_raw)]
+    pub fn datetime_from_ulid(ulid: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::ulid_millis(ulid)
+            .ok_or(Box::<EvalAltResult>::from(format!("Invalid ULID {}", ulid)))
+            .and_then(datetime_millis)
+    }
+
+    /// Construct DateTime with the timestamp of a version 1, 6 or 7 UUID
+    #[rhai_fn(return_raw)]
+    pub fn datetime_from_uuid(uuid: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        match crate::epoch::uuid_time(uuid).map_err(Box::<EvalAltResult>::from)? {
+            crate::epoch::UuidTime::Gregorian(ticks) => crate::epoch::from_ticks(ticks, 10_000_000, crate::epoch::UUID_EPOCH)
+                .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+                .map(|dt| Shared::new(Locked::new(dt))),
+            crate::epoch::UuidTime::UnixMillis(millis) => datetime_millis(millis),
+        }
+    }
+
+    /// Construct DateTime with the timestamp of a Snowflake ID using the Twitter epoch
+    #[rhai_fn(return_raw, name = "datetime_from_snowflake")]
+    pub fn datetime_from_snowflake(id: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        datetime_from_snowflake_epoch(id, crate::epoch::TWITTER_SNOWFLAKE_EPOCH)
+    }
+
+    /// Construct DateTime with the timestamp of a Snowflake ID using a custom epoch in UNIX milliseconds
+    #[rhai_fn(return_raw, name = "datetime_from_snowflake")]
+    pub fn datetime_from_snowflake_epoch(id: rhai::INT, epoch_ms: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        if id < 0 {
+            return Err(Box::<EvalAltResult>::from(format!("Invalid Snowflake ID {}", id)));
+        }
+
+        (id >> 22)
+            .checked_add(epoch_ms)
+            .ok_or(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .and_then(datetime_millis)
+    }
+
+    /// Construct DateTime with the timestamp of a Snowflake ID given as string, as JSON APIs usually do, using the Twitter epoch
+    #[rhai_fn(return_raw, name = "datetime_from_snowflake")]
+    pub fn datetime_from_snowflake_str(id: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        datetime_from_snowflake_str_epoch(id, crate::epoch::TWITTER_SNOWFLAKE_EPOCH)
+    }
+
+    /// Construct DateTime with the timestamp of a Snowflake ID given as string using a custom epoch in UNIX milliseconds
+    #[rhai_fn(return_raw, name = "datetime_from_snowflake")]
+    pub fn datetime_from_snowflake_str_epoch(id: &str, epoch_ms: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let value = id
+            .trim()
+            .parse::<u64>()
+            .map_err(|_e| Box::<EvalAltResult>::from(format!("Invalid Snowflake ID {}", id)))?;
+
+        ((value >> 22) as rhai::INT)
+            .checked_add(epoch_ms)
+            .ok_or(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+            .and_then(datetime_millis)
+    }
+
+    /// Construct DateTime with the seconds timestamp of a MongoDB ObjectId
+    #[rhai_fn(return_raw)]
+    pub fn datetime_from_object_id(object_id: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::epoch::object_id_seconds(object_id)
+            .ok_or(Box::<EvalAltResult>::from(format!("Invalid ObjectId {}", object_id)))
+            .and_then(datetime_unix)
+    }
+
+    fn excel_system(system: rhai::INT) -> Result<bool, Box<EvalAltResult>> {
+        match system {
+            1900 => Ok(false),
+            1904 => Ok(true),
+            _ => Err(Box::<EvalAltResult>::from(format!(
+                "Invalid Excel date system provided: {}. Supported systems are 1900 and 1904",
+                system
+            ))),
+        }
+    }
+
     /// Construct DateTime with valid RFC2822 timestamp
     #[rhai_fn(return_raw)]
     pub fn datetime_rfc2822(timestamp: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
@@ -93,6 +319,42 @@ pub mod datetime_module {
             .map(|dt| Shared::new(Locked::new(dt)))
     }
 
+    /// Construct DateTime with HTTP date in IMF-fixdate, RFC850 or asctime format (RFC9110)
+    #[rhai_fn(return_raw)]
+    pub fn datetime_http(timestamp: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let timestamp = timestamp.trim();
+
+        if let Ok(dt) = NaiveDateTime::parse_from_str(timestamp, "%a, %d %b %Y %H:%M:%S GMT") {
+            return Ok(Shared::new(Locked::new(dt.and_utc().fixed_offset())));
+        }
+
+        if let Ok(dt) = NaiveDateTime::parse_from_str(timestamp, "%a %b %e %H:%M:%S %Y") {
+            return Ok(Shared::new(Locked::new(dt.and_utc().fixed_offset())));
+        }
+
+        NaiveDateTime::parse_from_str(timestamp, "%A, %d-%b-%y %H:%M:%S GMT")
+            .map_err(|e| {
+                Box::new(EvalAltResult::ErrorSystem(
+                    format!("Failed to parse HTTP date {}", timestamp),
+                    Box::new(e),
+                ))
+            })
+            .and_then(|dt| {
+                // RFC850 two digit years more than 50 years in the future represent the most recent past year with the same digits
+                let now = Utc::now().year();
+                let mut year = now - now.rem_euclid(100) + dt.year().rem_euclid(100);
+
+                if year > now + 50 {
+                    year -= 100;
+                }
+
+                dt.with_year(year).ok_or(Box::<EvalAltResult>::from(
+                    "Year out of range or doesn't make any sense.".to_string(),
+                ))
+            })
+            .map(|dt| Shared::new(Locked::new(dt.and_utc().fixed_offset())))
+    }
+
     /// Construct DateTime with valid RFC3339 timestamp
     #[rhai_fn(return_raw)]
     pub fn datetime_rfc3339(timestamp: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
@@ -106,6 +368,178 @@ pub mod datetime_module {
             .map(|dt| Shared::new(Locked::new(dt)))
     }
 
+    /// Construct DateTime with BSD syslog timestamp (RFC3164), i.e. "Oct 11 22:14:15", in the given year
+    #[rhai_fn(return_raw, name = "datetime_rfc3164", name = "datetime_syslog")]
+    pub fn datetime_rfc3164_with_year(timestamp: &str, year: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        NaiveDateTime::parse_from_str(&format!("{} {}", year, timestamp.trim()), "%Y %b %e %H:%M:%S")
+            .map_err(|e| {
+                Box::new(EvalAltResult::ErrorSystem(
+                    "Failed to parse RFC3164 timestamp".to_string(),
+                    Box::new(e),
+                ))
+            })
+            .map(|dt| Shared::new(Locked::new(dt.and_utc().fixed_offset())))
+    }
+
+    /// Construct DateTime with BSD syslog timestamp (RFC3164), inferring the year from the current UTC time
+    #[rhai_fn(return_raw, name = "datetime_rfc3164", name = "datetime_syslog")]
+    pub fn datetime_rfc3164(timestamp: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let now = Utc::now();
+        let mut error = None;
+
+        // a timestamp more than a day ahead was logged in an earlier year, i.e. "Dec 31" read on Jan 1st, and "Feb 29" only
+        // exists in leap years, which can be up to 8 years apart around a century
+        for year in (now.year() - 8..=now.year()).rev() {
+            match datetime_rfc3164_with_year(timestamp, year as rhai::INT) {
+                Ok(dt) if *borrow_mut(&dt) <= now + chrono::TimeDelta::days(1) => return Ok(dt),
+                Ok(_) => {}
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+
+        Err(error.unwrap_or_else(crate::calendar::out_of_range))
+    }
+
+    /// Construct DateTime with syslog timestamp (RFC5424)
+    #[rhai_fn(return_raw)]
+    pub fn datetime_rfc5424(timestamp: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        if timestamp.trim() == "-" {
+            return Err(Box::<EvalAltResult>::from("RFC5424 timestamp is NILVALUE".to_string()));
+        }
+
+        datetime_rfc3339(timestamp.trim()).map_err(|_e| Box::<EvalAltResult>::from("Failed to parse RFC5424 timestamp".to_string()))
+    }
+
+    /// Construct DateTime with Common Log Format timestamp, i.e. "[10/Oct/2000:13:55:36 -0700]"
+    #[rhai_fn(return_raw)]
+    pub fn datetime_clf(timestamp: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let timestamp = timestamp.trim().trim_start_matches('[').trim_end_matches(']');
+
+        DateTime::parse_from_str(timestamp, "%d/%b/%Y:%H:%M:%S %z")
+            .map_err(|e| {
+                Box::new(EvalAltResult::ErrorSystem(
+                    "Failed to parse Common Log Format timestamp".to_string(),
+                    Box::new(e),
+                ))
+            })
+            .map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime with Java/Log4j timestamp, i.e. "2024-01-01 12:00:00,123"
+    #[rhai_fn(return_raw)]
+    pub fn datetime_log4j(timestamp: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        NaiveDateTime::parse_from_str(timestamp.trim(), "%Y-%m-%d %H:%M:%S,%3f")
+            .map_err(|e| {
+                Box::new(EvalAltResult::ErrorSystem(
+                    "Failed to parse Log4j timestamp".to_string(),
+                    Box::new(e),
+                ))
+            })
+            .map(|dt| Shared::new(Locked::new(dt.and_utc().fixed_offset())))
+    }
+
+    // SQLite time values take a space or "T" separator, optional seconds and fraction, and an optional "Z" or "+HH:MM" suffix
+    fn sqlite_text(value: &str) -> Result<DateTime<FixedOffset>, chrono::ParseError> {
+        let value = match value.get(10..11) {
+            Some("T" | "t") => format!("{} {}", &value[..10], &value[11..]),
+            _ => value.to_string(),
+        };
+
+        NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S%.f")
+            .or_else(|_e| NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M"))
+            .map(|dt| dt.and_utc().fixed_offset())
+            .or_else(|_e| DateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S%.f%#z"))
+            .or_else(|_e| DateTime::parse_from_str(&value, "%Y-%m-%d %H:%M%#z"))
+    }
+
+    /// Construct DateTime from database text using "mysql", "postgres", "sqlite", "sqlite_julian", "sqlite_unix" or "mssql" dialect
+    #[rhai_fn(return_raw, name = "datetime_sql")]
+    pub fn datetime_sql(value: &str, dialect: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let value = value.trim();
+
+        let parse_error = |e: chrono::ParseError| {
+            Box::new(EvalAltResult::ErrorSystem(
+                format!("Failed to parse {} timestamp {}", dialect, value),
+                Box::new(e),
+            ))
+        };
+
+        let dt = match dialect.to_lowercase().as_str() {
+            "mysql" => NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+                .map_err(parse_error)?
+                .and_utc()
+                .fixed_offset(),
+            "sqlite" => sqlite_text(value).map_err(parse_error)?,
+            "postgres" | "postgresql" => DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%#z").map_err(parse_error)?,
+            "mssql" | "sqlserver" => DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f %:z").map_err(parse_error)?,
+            #[cfg(not(feature = "no_float"))]
+            "sqlite_julian" => {
+                return datetime_sql_float(
+                    value
+                        .parse()
+                        .map_err(|_e| Box::<EvalAltResult>::from(format!("Failed to parse {} timestamp {}", dialect, value)))?,
+                    dialect,
+                )
+            }
+            "sqlite_unix" => {
+                return datetime_sql_int(
+                    value
+                        .parse()
+                        .map_err(|_e| Box::<EvalAltResult>::from(format!("Failed to parse {} timestamp {}", dialect, value)))?,
+                    dialect,
+                )
+            }
+            _ => return Err(crate::format::invalid_dialect(dialect)),
+        };
+
+        Ok(Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime from SQLite unix epoch integer using "sqlite_unix" dialect
+    #[rhai_fn(return_raw, name = "datetime_sql")]
+    pub fn datetime_sql_int(value: rhai::INT, dialect: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        match dialect.to_lowercase().as_str() {
+            "sqlite_unix" => datetime_unix(value),
+            _ => Err(crate::format::invalid_dialect(dialect)),
+        }
+    }
+
+    /// Construct DateTime from SQLite julian day number using "sqlite_julian" dialect
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(return_raw, name = "datetime_sql")]
+    pub fn datetime_sql_float(value: rhai::FLOAT, dialect: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        match dialect.to_lowercase().as_str() {
+            "sqlite_julian" => crate::epoch::from_julian_day(value)
+                .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from("Timestamp out of range".to_string()))
+                .map(|dt| Shared::new(Locked::new(dt))),
+            _ => Err(crate::format::invalid_dialect(dialect)),
+        }
+    }
+
+    /// Construct DateTime from English relative date expression, i.e. "tomorrow 9am" or "in 2 weeks", based on current UTC time
+    #[rhai_fn(return_raw, name = "datetime_relative")]
+    pub fn datetime_relative_now(expr: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        crate::relative::parse_relative(expr, Utc::now().fixed_offset(), &Vec::new()).map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime from English relative date expression based on the given DateTime
+    #[rhai_fn(return_raw, name = "datetime_relative")]
+    pub fn datetime_relative(expr: &str, base: DateTimeFixed) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let base = *borrow_mut(&base);
+
+        crate::relative::parse_relative(expr, base, &Vec::new()).map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Construct DateTime from relative date expression based on the given DateTime, translating words through a map of phrases to English
+    #[rhai_fn(return_raw, name = "datetime_relative")]
+    pub fn datetime_relative_words(expr: &str, base: DateTimeFixed, words: rhai::Map) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let base = *borrow_mut(&base);
+
+        crate::relative::parse_relative(expr, base, &crate::relative::word_table(words)?).map(|dt| Shared::new(Locked::new(dt)))
+    }
+
     /// Construct DateTime from custom timestamp and format
     /// See the [format::strftime](https://docs.rs/chrono/latest/chrono/format/strftime/index.html) module for supported format sequences.
     #[rhai_fn(return_raw)]
@@ -120,18 +554,158 @@ pub mod datetime_module {
             .map(|dt| Shared::new(Locked::new(dt.and_utc().fixed_offset())))
     }
 
+    /// Construct DateTime from custom timestamp and precompiled format
+    #[rhai_fn(return_raw, name = "datetime_parse")]
+    pub fn datetime_parse_compiled(timestamp: &str, format: DateTimeFormat) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let mut parsed = chrono::format::Parsed::new();
+
+        chrono::format::parse(&mut parsed, timestamp, format.items.iter())
+            .and_then(|_| parsed.to_naive_datetime_with_offset(0))
+            .map_err(|e| {
+                Box::new(EvalAltResult::ErrorSystem(
+                    format!("Failed to parse timestamp {} using format {}", timestamp, format.source),
+                    Box::new(e),
+                ))
+            })
+            .map(|dt| Shared::new(Locked::new(dt.and_utc().fixed_offset())))
+    }
+
+    /// Construct DateTime from custom timestamp and format with month and weekday names in the given locale
+    ///
+    /// See the [Locale](https://docs.rs/chrono/latest/chrono/enum.Locale.html) enum for list of valid locales
+    #[rhai_fn(return_raw, name = "datetime_parse")]
+    pub fn datetime_parse_localized(timestamp: &str, format: &str, locale: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let locale = crate::calendar::parse_locale(locale)?;
+
+        NaiveDateTime::parse_from_str(&crate::format::delocalize(timestamp, format, locale), format)
+            .map_err(|e| {
+                Box::new(EvalAltResult::ErrorSystem(
+                    format!("Failed to parse timestamp {} using format {}", timestamp, format),
+                    Box::new(e),
+                ))
+            })
+            .map(|dt| Shared::new(Locked::new(dt.and_utc().fixed_offset())))
+    }
+
     /// Output RFC3339 string
     #[rhai_fn(global, name = "to_string", name = "to_rfc3339", pure)]
     pub fn to_string(dt: &mut DateTimeFixed) -> ImmutableString {
         borrow_mut(dt).to_rfc3339().into()
     }
 
+    /// Output RFC3339 string with given fractional seconds precision
+    #[rhai_fn(global, name = "to_rfc3339", pure, return_raw)]
+    pub fn to_rfc3339_with_precision(dt: &mut DateTimeFixed, precision: &str) -> Result<ImmutableString, Box<EvalAltResult>> {
+        to_rfc3339_with_options(dt, precision, false)
+    }
+
+    /// Output RFC3339 string with given fractional seconds precision, optionally using "Z" for UTC
+    #[rhai_fn(global, name = "to_rfc3339", pure, return_raw)]
+    pub fn to_rfc3339_with_options(dt: &mut DateTimeFixed, precision: &str, use_z: bool) -> Result<ImmutableString, Box<EvalAltResult>> {
+        let format = crate::format::seconds_format(precision)?;
+
+        Ok(borrow_mut(dt).to_rfc3339_opts(format, use_z).into())
+    }
+
+    /// Output compact ISO 8601 basic format string, i.e. 20240101T120000Z
+    #[rhai_fn(global, name = "to_iso8601_basic", pure)]
+    pub fn to_iso8601_basic(dt: &mut DateTimeFixed) -> ImmutableString {
+        let this = borrow_mut(dt);
+
+        if this.offset().local_minus_utc() == 0 {
+            this.format("%Y%m%dT%H%M%SZ").to_string().into()
+        } else {
+            this.format("%Y%m%dT%H%M%S%z").to_string().into()
+        }
+    }
+
     /// Output RFC2822 string
     #[rhai_fn(global, name = "to_rfc2822", pure)]
     pub fn to_rfc2822(dt: &mut DateTimeFixed) -> ImmutableString {
         borrow_mut(dt).to_rfc2822().into()
     }
 
+    /// Output HTTP date in IMF-fixdate format, always in GMT (RFC9110); also used for cookie Expires attribute (RFC6265)
+    #[rhai_fn(global, name = "to_http_date", name = "to_cookie_expires", pure)]
+    pub fn to_http_date(dt: &mut DateTimeFixed) -> ImmutableString {
+        borrow_mut(dt)
+            .with_timezone(&Utc)
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string()
+            .into()
+    }
+
+    /// Output BSD syslog timestamp (RFC3164), i.e. "Oct  1 22:14:15"
+    #[rhai_fn(global, name = "to_rfc3164", name = "to_syslog", pure)]
+    pub fn to_rfc3164(dt: &mut DateTimeFixed) -> ImmutableString {
+        borrow_mut(dt).format("%b %e %H:%M:%S").to_string().into()
+    }
+
+    /// Output syslog timestamp (RFC5424) with up to microsecond precision
+    #[rhai_fn(global, name = "to_rfc5424", pure)]
+    pub fn to_rfc5424(dt: &mut DateTimeFixed) -> ImmutableString {
+        let this = borrow_mut(dt);
+
+        if this.timestamp_subsec_nanos() == 0 {
+            this.to_rfc3339_opts(chrono::SecondsFormat::Secs, true).into()
+        } else {
+            this.to_rfc3339_opts(chrono::SecondsFormat::Micros, true).into()
+        }
+    }
+
+    /// Output Common Log Format timestamp, i.e. "[10/Oct/2000:13:55:36 -0700]"
+    #[rhai_fn(global, name = "to_clf", pure)]
+    pub fn to_clf(dt: &mut DateTimeFixed) -> ImmutableString {
+        borrow_mut(dt).format("[%d/%b/%Y:%H:%M:%S %z]").to_string().into()
+    }
+
+    /// Output Java/Log4j timestamp, i.e. "2024-01-01 12:00:00,123"
+    #[rhai_fn(global, name = "to_log4j", pure)]
+    pub fn to_log4j(dt: &mut DateTimeFixed) -> ImmutableString {
+        borrow_mut(dt).format("%Y-%m-%d %H:%M:%S,%3f").to_string().into()
+    }
+
+    /// Output database value for "mysql", "postgres", "sqlite", "sqlite_julian", "sqlite_unix" or "mssql" dialect
+    #[rhai_fn(global, name = "to_sql", pure, return_raw)]
+    pub fn to_sql(dt: &mut DateTimeFixed, dialect: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        let this = *borrow_mut(dt);
+        let utc = this.naive_utc();
+
+        let value = match dialect.to_lowercase().as_str() {
+            // DATETIME(6) has no offset, so the value is stored in UTC
+            "mysql" if utc.nanosecond() / 1000 == 0 => utc.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "mysql" => utc.format("%Y-%m-%d %H:%M:%S%.6f").to_string(),
+            "postgres" | "postgresql" => {
+                let micros = format!("{:06}", this.nanosecond() / 1000);
+                let fraction = micros.trim_end_matches('0');
+                let offset = this.format("%:z").to_string();
+
+                format!(
+                    "{}{}{}{}",
+                    this.format("%Y-%m-%d %H:%M:%S"),
+                    if fraction.is_empty() { "" } else { "." },
+                    fraction,
+                    offset.strip_suffix(":00").unwrap_or(&offset)
+                )
+            }
+            // SQLite date and time functions expect UTC text with millisecond precision
+            "sqlite" => utc.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            #[cfg(not(feature = "no_float"))]
+            "sqlite_julian" => return Ok(Dynamic::from_float(crate::epoch::julian_day(&this))),
+            "sqlite_unix" => return Ok(Dynamic::from_int(this.timestamp() as rhai::INT)),
+            // DATETIMEOFFSET(7) has 100 nanosecond precision
+            "mssql" | "sqlserver" => format!(
+                "{}.{:07} {}",
+                this.format("%Y-%m-%d %H:%M:%S"),
+                this.nanosecond() % 1_000_000_000 / 100,
+                this.format("%:z")
+            ),
+            _ => return Err(crate::format::invalid_dialect(dialect)),
+        };
+
+        Ok(value.into())
+    }
+
     /// Output UNIX timestamp i64
     #[rhai_fn(global, name = "timestamp", pure)]
     pub fn timestamp(dt: &mut DateTimeFixed) -> rhai::INT {
@@ -160,6 +734,84 @@ pub mod datetime_module {
             ))
     }
 
+    /// Output Excel serial day number in the 1900 date system
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "to_excel", pure, return_raw)]
+    pub fn to_excel(dt: &mut DateTimeFixed) -> Result<rhai::FLOAT, Box<EvalAltResult>> {
+        to_excel_system(dt, 1900)
+    }
+
+    /// Output Excel serial day number in the 1900 or 1904 date system
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "to_excel", pure, return_raw)]
+    pub fn to_excel_system(dt: &mut DateTimeFixed, system: rhai::INT) -> Result<rhai::FLOAT, Box<EvalAltResult>> {
+        crate::epoch::to_excel(&borrow_mut(dt), excel_system(system)?).ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from(
+            "Timestamp out of range (Excel supports dates from 1900 to 9999)".to_string(),
+        ))
+    }
+
+    /// Output Windows FILETIME
+    #[rhai_fn(global, name = "to_filetime", pure, return_raw)]
+    pub fn to_filetime(dt: &mut DateTimeFixed) -> Result<rhai::INT, Box<EvalAltResult>> {
+        crate::epoch::to_ticks(&borrow_mut(dt), 10_000_000, crate::epoch::FILETIME_EPOCH)
+            .filter(|ticks| *ticks >= 0)
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from(
+                "Timestamp out of range (FILETIME starts at 1601-01-01)".to_string(),
+            ))
+    }
+
+    /// Output .NET ticks
+    #[rhai_fn(global, name = "to_dotnet_ticks", pure, return_raw)]
+    pub fn to_dotnet_ticks(dt: &mut DateTimeFixed) -> Result<rhai::INT, Box<EvalAltResult>> {
+        crate::epoch::to_ticks(&borrow_mut(dt), 10_000_000, crate::epoch::DOTNET_EPOCH)
+            .filter(|ticks| (0..=crate::epoch::DOTNET_MAX_TICKS).contains(ticks))
+            .ok_or::<Box<EvalAltResult>>(Box::<EvalAltResult>::from(
+                "Timestamp out of range (.NET supports dates from 0001 to 9999)".to_string(),
+            ))
+    }
+
+    /// Output NTP seconds since 1900-01-01 UTC
+    #[rhai_fn(global, name = "to_ntp", pure)]
+    pub fn to_ntp(dt: &mut DateTimeFixed) -> rhai::INT {
+        borrow_mut(dt).timestamp() - crate::epoch::NTP_EPOCH
+    }
+
+    /// Output NTP 32-bit fraction of a second
+    #[rhai_fn(global, name = "to_ntp_fraction", pure)]
+    pub fn to_ntp_fraction(dt: &mut DateTimeFixed) -> rhai::INT {
+        let nanos = (borrow_mut(dt).timestamp_subsec_nanos() % 1_000_000_000) as u64;
+
+        (nanos << 32).div_ceil(1_000_000_000) as rhai::INT
+    }
+
+    /// Output Apple Cocoa seconds since 2001-01-01 UTC
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "to_cocoa", pure)]
+    pub fn to_cocoa(dt: &mut DateTimeFixed) -> rhai::FLOAT {
+        crate::epoch::to_seconds(&borrow_mut(dt), crate::epoch::COCOA_EPOCH)
+    }
+
+    /// Output fractional Julian Day number
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "julian_day", pure)]
+    pub fn julian_day(dt: &mut DateTimeFixed) -> rhai::FLOAT {
+        crate::epoch::julian_day(&borrow_mut(dt))
+    }
+
+    /// Output fractional Modified Julian Day number
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "mjd", pure)]
+    pub fn mjd(dt: &mut DateTimeFixed) -> rhai::FLOAT {
+        crate::epoch::modified_julian_day(&borrow_mut(dt))
+    }
+
+    /// Output fractional UNIX timestamp in seconds
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "timestamp_f64", pure)]
+    pub fn timestamp_f64(dt: &mut DateTimeFixed) -> rhai::FLOAT {
+        crate::epoch::to_seconds(&borrow_mut(dt), 0)
+    }
+
     /// Returns the number of milliseconds since the last second boundary.
     #[rhai_fn(global, name = "timestamp_subsec_millis", pure)]
     pub fn timestamp_subsec_millis(dt: &mut DateTimeFixed) -> rhai::INT {
@@ -207,9 +859,9 @@ pub mod datetime_module {
     /// Formats the combined date and time per the specified format string.
     ///
     /// See the [format::strftime](https://docs.rs/chrono/latest/chrono/format/strftime/index.html) module for the supported escape sequences.
-    #[rhai_fn(global, name = "format", pure)]
-    pub fn format(dt: &mut DateTimeFixed, format: &str) -> String {
-        format!("{}", borrow_mut(dt).format(format))
+    #[rhai_fn(global, name = "format", pure, return_raw)]
+    pub fn format(dt: &mut DateTimeFixed, format: &str) -> Result<String, Box<EvalAltResult>> {
+        crate::format::format_localized(&borrow_mut(dt), format, Locale::POSIX)
     }
 
     /// Formats the combined date and time per the specified format string and locale.
@@ -221,7 +873,60 @@ pub mod datetime_module {
     pub fn format_localized(dt: &mut DateTimeFixed, format: &str, locale: &str) -> Result<String, Box<EvalAltResult>> {
         let locale = Locale::from_str(locale).map_err(|_e| Box::<EvalAltResult>::from(format!("Invalid locale provided: {}", locale)))?;
 
-        Ok(format!("{}", borrow_mut(dt).format_localized(format, locale)))
+        crate::format::format_localized(&borrow_mut(dt), format, locale)
+    }
+
+    /// Formats the combined date and time using a precompiled format.
+    #[rhai_fn(global, name = "format", pure, return_raw)]
+    pub fn format_compiled(dt: &mut DateTimeFixed, format: DateTimeFormat) -> Result<String, Box<EvalAltResult>> {
+        crate::format::format_compiled(&borrow_mut(dt), &format)
+    }
+
+    /// Formats the combined date and time using a "short", "medium", "long" or "full" preset.
+    #[rhai_fn(global, name = "format_style", pure, return_raw)]
+    pub fn format_style(dt: &mut DateTimeFixed, style: &str) -> Result<String, Box<EvalAltResult>> {
+        format_style_localized(dt, style, "POSIX")
+    }
+
+    /// Formats the combined date and time using a "short", "medium", "long" or "full" preset of the locale.
+    ///
+    /// Presets are derived from the date and time conventions (D_FMT and T_FMT) of the [Locale](https://docs.rs/chrono/latest/chrono/enum.Locale.html).
+    #[rhai_fn(global, name = "format_style", pure, return_raw)]
+    pub fn format_style_localized(dt: &mut DateTimeFixed, style: &str, locale: &str) -> Result<String, Box<EvalAltResult>> {
+        let locale = crate::calendar::parse_locale(locale)?;
+        let pattern = crate::format::datetime_pattern(style, locale)?;
+
+        crate::format::format_localized(&borrow_mut(dt), &pattern, locale)
+    }
+
+    /// Formats the date using a "short", "medium", "long" or "full" preset.
+    #[rhai_fn(global, name = "format_date_style", pure, return_raw)]
+    pub fn format_date_style(dt: &mut DateTimeFixed, style: &str) -> Result<String, Box<EvalAltResult>> {
+        format_date_style_localized(dt, style, "POSIX")
+    }
+
+    /// Formats the date using a "short", "medium", "long" or "full" preset of the locale.
+    #[rhai_fn(global, name = "format_date_style", pure, return_raw)]
+    pub fn format_date_style_localized(dt: &mut DateTimeFixed, style: &str, locale: &str) -> Result<String, Box<EvalAltResult>> {
+        let locale = crate::calendar::parse_locale(locale)?;
+        let pattern = crate::format::date_pattern(style, locale)?;
+
+        crate::format::format_localized(&borrow_mut(dt), &pattern, locale)
+    }
+
+    /// Formats the time using a "short", "medium", "long" or "full" preset.
+    #[rhai_fn(global, name = "format_time_style", pure, return_raw)]
+    pub fn format_time_style(dt: &mut DateTimeFixed, style: &str) -> Result<String, Box<EvalAltResult>> {
+        format_time_style_localized(dt, style, "POSIX")
+    }
+
+    /// Formats the time using a "short", "medium", "long" or "full" preset of the locale.
+    #[rhai_fn(global, name = "format_time_style", pure, return_raw)]
+    pub fn format_time_style_localized(dt: &mut DateTimeFixed, style: &str, locale: &str) -> Result<String, Box<EvalAltResult>> {
+        let locale = crate::calendar::parse_locale(locale)?;
+        let pattern = crate::format::time_pattern(style, locale)?;
+
+        crate::format::format_localized(&borrow_mut(dt), &pattern, locale)
     }
 
     /// Set timezone or offset
@@ -571,6 +1276,132 @@ pub mod datetime_module {
         this.nanosecond() as rhai::INT
     }
 
+    /// Get the quarter of the year
+    #[rhai_fn(global, get = "quarter", name = "quarter", name = "get_quarter", pure)]
+    pub fn get_quarter(dt: &mut DateTimeFixed) -> rhai::INT {
+        let this = borrow_mut(dt);
+
+        (this.month0() / 3 + 1) as rhai::INT
+    }
+
+    /// Get the half of the year
+    #[rhai_fn(global, get = "half", name = "half", name = "get_half", pure)]
+    pub fn get_half(dt: &mut DateTimeFixed) -> rhai::INT {
+        let this = borrow_mut(dt);
+
+        (this.month0() / 6 + 1) as rhai::INT
+    }
+
+    /// Get the number of days in the month
+    #[rhai_fn(global, get = "days_in_month", name = "days_in_month", pure)]
+    pub fn get_days_in_month(dt: &mut DateTimeFixed) -> rhai::INT {
+        let this = borrow_mut(dt);
+
+        crate::calendar::days_in_month(this.year(), this.month()) as rhai::INT
+    }
+
+    /// Check if the year is a leap year
+    #[rhai_fn(global, get = "is_leap_year", name = "is_leap_year", pure)]
+    pub fn get_is_leap_year(dt: &mut DateTimeFixed) -> bool {
+        let this = borrow_mut(dt);
+
+        crate::calendar::is_leap_year(this.year() as rhai::INT)
+    }
+
+    /// Move to the start of the year, half, quarter, month, week, day, hour, minute or second
+    #[rhai_fn(global, name = "start_of", pure, return_raw)]
+    pub fn start_of(dt: &mut DateTimeFixed, unit: &str) -> Result<(), Box<EvalAltResult>> {
+        let mut this = borrow_mut(dt);
+
+        *this = crate::calendar::start_of(&this, unit)?;
+
+        Ok(())
+    }
+
+    /// Move to the last nanosecond of the year, half, quarter, month, week, day, hour, minute or second
+    #[rhai_fn(global, name = "end_of", pure, return_raw)]
+    pub fn end_of(dt: &mut DateTimeFixed, unit: &str) -> Result<(), Box<EvalAltResult>> {
+        let mut this = borrow_mut(dt);
+
+        *this = crate::calendar::end_of(&this, unit)?;
+
+        Ok(())
+    }
+
+    /// Get the fiscal year for a fiscal year starting on the 1st of the given month
+    #[rhai_fn(global, name = "fiscal_year", pure, return_raw)]
+    pub fn fiscal_year(dt: &mut DateTimeFixed, start_month: rhai::INT) -> Result<rhai::INT, Box<EvalAltResult>> {
+        let this = borrow_mut(dt);
+
+        Ok(crate::calendar::fiscal_year(&this, crate::calendar::check_month(start_month)?) as rhai::INT)
+    }
+
+    /// Get the fiscal quarter for a fiscal year starting on the 1st of the given month
+    #[rhai_fn(global, name = "fiscal_quarter", pure, return_raw)]
+    pub fn fiscal_quarter(dt: &mut DateTimeFixed, start_month: rhai::INT) -> Result<rhai::INT, Box<EvalAltResult>> {
+        let this = borrow_mut(dt);
+
+        Ok(crate::calendar::fiscal_quarter(&this, crate::calendar::check_month(start_month)?) as rhai::INT)
+    }
+
+    /// Get the start of the fiscal year starting on the 1st of the given month
+    #[rhai_fn(global, name = "fiscal_year_start", pure, return_raw)]
+    pub fn fiscal_year_start(dt: &mut DateTimeFixed, start_month: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let this = borrow_mut(dt);
+
+        crate::calendar::fiscal_year_start(&this, crate::calendar::check_month(start_month)?).map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Get the last nanosecond of the fiscal year starting on the 1st of the given month
+    #[rhai_fn(global, name = "fiscal_year_end", pure, return_raw)]
+    pub fn fiscal_year_end(dt: &mut DateTimeFixed, start_month: rhai::INT) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let this = borrow_mut(dt);
+
+        crate::calendar::fiscal_year_end(&this, crate::calendar::check_month(start_month)?).map(|dt| Shared::new(Locked::new(dt)))
+    }
+
+    /// Get the position within a 52-53 week retail calendar ending on the last given weekday of the given month
+    #[rhai_fn(global, name = "retail_period", pure, return_raw)]
+    pub fn retail_period(
+        dt: &mut DateTimeFixed,
+        end_month: rhai::INT,
+        end_weekday: &str,
+        pattern: &str,
+    ) -> Result<rhai::Map, Box<EvalAltResult>> {
+        retail_period_with_rule(dt, end_month, end_weekday, "last", pattern)
+    }
+
+    /// Get the position within a 52-53 week retail calendar ending on the given weekday of the given month using "last" or "nearest" rule
+    #[rhai_fn(global, name = "retail_period", pure, return_raw)]
+    pub fn retail_period_with_rule(
+        dt: &mut DateTimeFixed,
+        end_month: rhai::INT,
+        end_weekday: &str,
+        rule: &str,
+        pattern: &str,
+    ) -> Result<rhai::Map, Box<EvalAltResult>> {
+        let this = borrow_mut(dt);
+
+        let nearest = match rule.to_lowercase().as_str() {
+            "last" => false,
+            "nearest" => true,
+            _ => {
+                return Err(Box::<EvalAltResult>::from(format!(
+                    "Invalid rule provided: {}. Supported rules are last and nearest",
+                    rule
+                )))
+            }
+        };
+
+        crate::calendar::retail_period(
+            &this,
+            crate::calendar::check_month(end_month)?,
+            crate::calendar::parse_weekday(end_weekday)?,
+            nearest,
+            pattern,
+        )
+    }
+
     /// Add number of days
     #[rhai_fn(global, name = "add_days", name = "plus_days", pure, return_raw)]
     pub fn add_days(dt: &mut DateTimeFixed, days: rhai::INT) -> Result<(), Box<EvalAltResult>> {
@@ -650,19 +1481,182 @@ pub mod datetime_module {
     }
 
     /// Diff of two DateTime instances, producing TimeDelta (DateTime::signed_duration_since)
-    #[rhai_fn(
-        global,
-        name = "diff",
-        name = "cmp",
-        name = "compare",
-        name = "duration_since",
-        name = "signed_duration_since",
-        pure
-    )]
+    #[rhai_fn(global, name = "diff", name = "duration_since", name = "signed_duration_since", pure)]
     pub fn diff(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> Timedelta {
         let this = *borrow_mut(dt);
         let rhs = *borrow_mut(&rhs);
 
         Shared::new(Locked::new(this.signed_duration_since(rhs)))
     }
+
+    /// Order of two DateTime instances as -1, 0 or 1, comparing the instants regardless of offset
+    #[rhai_fn(global, name = "compare", name = "cmp", pure)]
+    pub fn compare(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> rhai::INT {
+        let this = *borrow_mut(dt);
+        let rhs = *borrow_mut(&rhs);
+
+        this.cmp(&rhs) as rhai::INT
+    }
+
+    /// Check if two DateTime instances are the same instant
+    #[rhai_fn(global, name = "==", pure)]
+    pub fn eq(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> bool {
+        compare(dt, rhs) == 0
+    }
+
+    /// Check if two DateTime instances are different instants
+    #[rhai_fn(global, name = "!=", pure)]
+    pub fn ne(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> bool {
+        compare(dt, rhs) != 0
+    }
+
+    /// Check if earlier than another DateTime
+    #[rhai_fn(global, name = "<", pure)]
+    pub fn lt(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> bool {
+        compare(dt, rhs) < 0
+    }
+
+    /// Check if earlier than or the same as another DateTime
+    #[rhai_fn(global, name = "<=", pure)]
+    pub fn le(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> bool {
+        compare(dt, rhs) <= 0
+    }
+
+    /// Check if later than another DateTime
+    #[rhai_fn(global, name = ">", pure)]
+    pub fn gt(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> bool {
+        compare(dt, rhs) > 0
+    }
+
+    /// Check if later than or the same as another DateTime
+    #[rhai_fn(global, name = ">=", pure)]
+    pub fn ge(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> bool {
+        compare(dt, rhs) >= 0
+    }
+
+    /// Earlier of two DateTime instances
+    #[rhai_fn(global, name = "min", pure)]
+    pub fn min(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> DateTimeFixed {
+        let this = *borrow_mut(dt);
+        let rhs = *borrow_mut(&rhs);
+
+        Shared::new(Locked::new(this.min(rhs)))
+    }
+
+    /// Later of two DateTime instances
+    #[rhai_fn(global, name = "max", pure)]
+    pub fn max(dt: &mut DateTimeFixed, rhs: DateTimeFixed) -> DateTimeFixed {
+        let this = *borrow_mut(dt);
+        let rhs = *borrow_mut(&rhs);
+
+        Shared::new(Locked::new(this.max(rhs)))
+    }
+
+    /// DateTime restricted to the range from `lo` to `hi`, keeping the offset of whichever is returned
+    #[rhai_fn(global, name = "clamp", pure, return_raw)]
+    pub fn clamp(dt: &mut DateTimeFixed, lo: DateTimeFixed, hi: DateTimeFixed) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let this = *borrow_mut(dt);
+        let lo = *borrow_mut(&lo);
+        let hi = *borrow_mut(&hi);
+
+        if lo > hi {
+            return Err(Box::<EvalAltResult>::from(
+                "Lower bound must not be greater than upper bound".to_string(),
+            ));
+        }
+
+        Ok(Shared::new(Locked::new(this.clamp(lo, hi))))
+    }
+
+    /// Check if two DateTime instances are at most `tolerance` apart
+    #[rhai_fn(global, name = "approx_eq", pure)]
+    pub fn approx_eq(dt: &mut DateTimeFixed, rhs: DateTimeFixed, tolerance: Timedelta) -> bool {
+        let this = *borrow_mut(dt);
+        let rhs = *borrow_mut(&rhs);
+        let tolerance = crate::timedelta::borrow_mut(&tolerance).abs();
+
+        this.signed_duration_since(rhs).abs() <= tolerance
+    }
+
+    /// Start of the fixed length bucket holding the DateTime, counted from midnight of 1970-01-01 in its offset
+    #[rhai_fn(global, name = "bucket", pure, return_raw)]
+    pub fn bucket(dt: &mut DateTimeFixed, step: Timedelta) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let step = BucketStep::from_delta(&crate::timedelta::borrow_mut(&step))?;
+
+        Ok(Shared::new(Locked::new(crate::calendar::bucket(
+            &borrow_mut(dt),
+            &step,
+            None,
+            None,
+        )?)))
+    }
+
+    /// Start of the calendar or fixed length bucket holding the DateTime, such as "day" or "15m"
+    #[rhai_fn(global, name = "bucket", pure, return_raw)]
+    pub fn bucket_by(dt: &mut DateTimeFixed, step: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let step = BucketStep::parse(step)?;
+
+        Ok(Shared::new(Locked::new(crate::calendar::bucket(
+            &borrow_mut(dt),
+            &step,
+            None,
+            None,
+        )?)))
+    }
+
+    /// Start of the fixed length bucket holding the DateTime, counted from an origin
+    #[rhai_fn(global, name = "bucket", pure, return_raw)]
+    pub fn bucket_from(dt: &mut DateTimeFixed, step: Timedelta, origin: DateTimeFixed) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let step = BucketStep::from_delta(&crate::timedelta::borrow_mut(&step))?;
+        let origin = *borrow_mut(&origin);
+
+        Ok(Shared::new(Locked::new(crate::calendar::bucket(
+            &borrow_mut(dt),
+            &step,
+            Some(origin),
+            None,
+        )?)))
+    }
+
+    /// Start of the fixed length bucket holding the DateTime, given as a string such as "15m" and counted from an origin
+    #[rhai_fn(global, name = "bucket", pure, return_raw)]
+    pub fn bucket_by_from(dt: &mut DateTimeFixed, step: &str, origin: DateTimeFixed) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let step = BucketStep::parse(step)?;
+        let origin = *borrow_mut(&origin);
+
+        Ok(Shared::new(Locked::new(crate::calendar::bucket(
+            &borrow_mut(dt),
+            &step,
+            Some(origin),
+            None,
+        )?)))
+    }
+
+    /// Start of the fixed length bucket holding the DateTime, expressed in an IANA timezone
+    #[rhai_fn(global, name = "bucket", pure, return_raw)]
+    pub fn bucket_in(dt: &mut DateTimeFixed, step: Timedelta, timezone: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let step = BucketStep::from_delta(&crate::timedelta::borrow_mut(&step))?;
+        let tz = crate::calendar::parse_timezone(timezone)?;
+
+        Ok(Shared::new(Locked::new(crate::calendar::bucket(
+            &borrow_mut(dt),
+            &step,
+            None,
+            Some(tz),
+        )?)))
+    }
+
+    /// Start of the calendar or fixed length bucket holding the DateTime, following the wall clock of an IANA timezone
+    #[rhai_fn(global, name = "bucket", pure, return_raw)]
+    pub fn bucket_by_in(dt: &mut DateTimeFixed, step: &str, timezone: &str) -> Result<DateTimeFixed, Box<EvalAltResult>> {
+        let step = BucketStep::parse(step)?;
+        let tz = crate::calendar::parse_timezone(timezone)?;
+
+        Ok(Shared::new(Locked::new(crate::calendar::bucket(
+            &borrow_mut(dt),
+            &step,
+            None,
+            Some(tz),
+        )?)))
+    }
 }
@@ -0,0 +1,292 @@
+use chrono::{DateTime, FixedOffset};
+
+/// Seconds from 1601-01-01 (Windows FILETIME epoch) to the UNIX epoch
+pub const FILETIME_EPOCH: i64 = -11_644_473_600;
+
+/// Seconds from 0001-01-01 (.NET ticks epoch) to the UNIX epoch
+pub const DOTNET_EPOCH: i64 = -62_135_596_800;
+
+/// Last .NET tick, being 9999-12-31T23:59:59.9999999
+pub const DOTNET_MAX_TICKS: i64 = 3_155_378_975_999_999_999;
+
+/// Seconds from 1900-01-01 (NTP epoch) to the UNIX epoch
+pub const NTP_EPOCH: i64 = -2_208_988_800;
+
+/// Seconds from 2001-01-01 (Apple Cocoa reference date) to the UNIX epoch
+pub const COCOA_EPOCH: i64 = 978_307_200;
+
+/// Seconds from 1899-12-30, day zero of the Excel 1900 date system past its fictitious Feb 29th 1900, to the UNIX epoch
+pub const EXCEL_1900_EPOCH: i64 = -2_209_161_600;
+
+/// Seconds from 1904-01-01, day zero of the Excel 1904 date system, to the UNIX epoch
+pub const EXCEL_1904_EPOCH: i64 = -2_082_844_800;
+
+/// First Excel serial past 9999-12-31 in the 1900 date system
+pub const EXCEL_END_DAY: i64 = 2_958_466;
+
+/// Largest Excel serial, being 9999-12-31 in the 1900 date system
+#[cfg(not(feature = "no_float"))]
+pub const EXCEL_MAX_SERIAL: rhai::FLOAT = EXCEL_END_DAY as rhai::FLOAT;
+
+/// Guess the unit of a UNIX timestamp from its magnitude, returned as ticks per second
+///
+/// Seconds are assumed below 10^11 (year 5138), milliseconds below 10^14, microseconds below 10^17 and nanoseconds above,
+/// so millisecond timestamps before 1973-03-03 are misread as seconds.
+pub fn unix_ticks_per_second(value: i64) -> i64 {
+    match value.unsigned_abs() {
+        0..100_000_000_000 => 1,
+        100_000_000_000..100_000_000_000_000 => 1_000,
+        100_000_000_000_000..100_000_000_000_000_000 => 1_000_000,
+        _ => 1_000_000_000,
+    }
+}
+
+/// DateTime from a count of fixed size ticks since an epoch given in UNIX seconds
+pub fn from_ticks(ticks: i64, ticks_per_second: i64, epoch: i64) -> Option<DateTime<FixedOffset>> {
+    let secs = ticks.div_euclid(ticks_per_second).checked_add(epoch)?;
+    let nanos = ticks.rem_euclid(ticks_per_second) * (1_000_000_000 / ticks_per_second);
+
+    DateTime::from_timestamp(secs, nanos as u32).map(|dt| dt.fixed_offset())
+}
+
+/// Count of fixed size ticks since an epoch given in UNIX seconds, truncating anything shorter than a tick
+pub fn to_ticks(dt: &DateTime<FixedOffset>, ticks_per_second: i64, epoch: i64) -> Option<i64> {
+    let secs = dt.timestamp().checked_sub(epoch)?;
+    let ticks = dt.timestamp_subsec_nanos() as i64 / (1_000_000_000 / ticks_per_second);
+
+    secs.checked_mul(ticks_per_second)?.checked_add(ticks)
+}
+
+/// DateTime from fractional seconds since an epoch given in UNIX seconds, rounded to the microsecond
+#[cfg(not(feature = "no_float"))]
+pub fn from_seconds(seconds: rhai::FLOAT, epoch: i64) -> Option<DateTime<FixedOffset>> {
+    let micros = (seconds * 1e6).round();
+
+    if !micros.is_finite() || micros.abs() >= i64::MAX as rhai::FLOAT {
+        return None;
+    }
+
+    from_ticks(micros as i64, 1_000_000, epoch)
+}
+
+/// Fractional seconds since an epoch given in UNIX seconds
+#[cfg(not(feature = "no_float"))]
+pub fn to_seconds(dt: &DateTime<FixedOffset>, epoch: i64) -> rhai::FLOAT {
+    (dt.timestamp() - epoch) as rhai::FLOAT + dt.timestamp_subsec_nanos() as rhai::FLOAT / 1e9
+}
+
+/// DateTime from Excel serial day number in the 1900 or 1904 date system
+///
+/// The 1900 system counts the non-existent Feb 29th 1900 as day 60, so serials from 61 onward are one day ahead of a real calendar.
+#[cfg(not(feature = "no_float"))]
+pub fn from_excel(serial: rhai::FLOAT, system_1904: bool) -> Option<DateTime<FixedOffset>> {
+    if !(0.0..EXCEL_MAX_SERIAL).contains(&serial) {
+        return None;
+    }
+
+    let serial = match (system_1904, serial) {
+        (true, _) => return from_seconds(serial * 86400.0, EXCEL_1904_EPOCH),
+        (false, s) if s >= 61.0 => s,
+        (false, s) if s >= 60.0 => return None,
+        (false, s) => s + 1.0,
+    };
+
+    from_seconds(serial * 86400.0, EXCEL_1900_EPOCH)
+}
+
+/// DateTime from whole Excel serial day number in the 1900 or 1904 date system, see `from_excel`
+pub fn from_excel_day(serial: i64, system_1904: bool) -> Option<DateTime<FixedOffset>> {
+    if !(0..EXCEL_END_DAY).contains(&serial) {
+        return None;
+    }
+
+    let (serial, epoch) = match (system_1904, serial) {
+        (true, s) => (s, EXCEL_1904_EPOCH),
+        (false, 60) => return None,
+        (false, s) if s > 60 => (s, EXCEL_1900_EPOCH),
+        (false, s) => (s + 1, EXCEL_1900_EPOCH),
+    };
+
+    from_ticks(serial * 86400, 1, epoch)
+}
+
+/// Excel serial day number in the 1900 or 1904 date system
+#[cfg(not(feature = "no_float"))]
+pub fn to_excel(dt: &DateTime<FixedOffset>, system_1904: bool) -> Option<rhai::FLOAT> {
+    let epoch = if system_1904 { EXCEL_1904_EPOCH } else { EXCEL_1900_EPOCH };
+    let mut serial = to_seconds(dt, epoch) / 86400.0;
+
+    if !system_1904 && serial < 61.0 {
+        serial -= 1.0;
+    }
+
+    if (0.0..EXCEL_MAX_SERIAL).contains(&serial) {
+        Some(serial)
+    } else {
+        None
+    }
+}
+
+/// Julian Day of the UNIX epoch
+#[cfg(not(feature = "no_float"))]
+const UNIX_EPOCH_JULIAN_DAY: rhai::FLOAT = 2440587.5;
+
+/// Modified Julian Day of the UNIX epoch, being JD 2440587.5 - 2400000.5
+#[cfg(not(feature = "no_float"))]
+const UNIX_EPOCH_MODIFIED_JULIAN_DAY: rhai::FLOAT = 40587.0;
+
+/// Whole Julian Day starting at noon of the UNIX epoch
+const UNIX_NOON_JULIAN_DAY_NUMBER: i64 = 2440588;
+
+/// Whole Modified Julian Day starting at the UNIX epoch
+const UNIX_EPOCH_MODIFIED_JULIAN_DAY_NUMBER: i64 = 40587;
+
+/// Fractional day count since the day given for the UNIX epoch
+///
+/// Whole days and the time of day are added separately so the fraction isn't lost to the magnitude of the day number first.
+#[cfg(not(feature = "no_float"))]
+fn days_since(dt: &DateTime<FixedOffset>, epoch_day: rhai::FLOAT) -> rhai::FLOAT {
+    let days = dt.timestamp().div_euclid(86400) as rhai::FLOAT;
+    let seconds = dt.timestamp().rem_euclid(86400) as rhai::FLOAT + dt.timestamp_subsec_nanos() as rhai::FLOAT / 1e9;
+
+    (days + epoch_day) + seconds / 86400.0
+}
+
+/// Fractional Julian Day number of the DateTime
+#[cfg(not(feature = "no_float"))]
+pub fn julian_day(dt: &DateTime<FixedOffset>) -> rhai::FLOAT {
+    days_since(dt, UNIX_EPOCH_JULIAN_DAY)
+}
+
+/// Fractional Modified Julian Day number of the DateTime
+#[cfg(not(feature = "no_float"))]
+pub fn modified_julian_day(dt: &DateTime<FixedOffset>) -> rhai::FLOAT {
+    days_since(dt, UNIX_EPOCH_MODIFIED_JULIAN_DAY)
+}
+
+/// DateTime from fractional Julian Day number, rounded to the microsecond
+///
+/// Present-day Julian Day numbers are around 2.46 million, where neighbouring floats are about 40 microseconds apart, so the
+/// result is no more precise than that.
+#[cfg(not(feature = "no_float"))]
+pub fn from_julian_day(julian_day: rhai::FLOAT) -> Option<DateTime<FixedOffset>> {
+    from_seconds((julian_day - UNIX_EPOCH_JULIAN_DAY) * 86400.0, 0)
+}
+
+/// DateTime from fractional Modified Julian Day number, rounded to the microsecond
+///
+/// Present-day Modified Julian Day numbers are small enough for neighbouring floats to be less than a microsecond apart.
+#[cfg(not(feature = "no_float"))]
+pub fn from_modified_julian_day(modified_julian_day: rhai::FLOAT) -> Option<DateTime<FixedOffset>> {
+    from_seconds((modified_julian_day - UNIX_EPOCH_MODIFIED_JULIAN_DAY) * 86400.0, 0)
+}
+
+/// DateTime from whole Julian Day number, being noon UTC
+pub fn from_julian_day_number(julian_day: i64) -> Option<DateTime<FixedOffset>> {
+    let days = julian_day.checked_sub(UNIX_NOON_JULIAN_DAY_NUMBER)?;
+
+    from_ticks(days.checked_mul(86400)?.checked_add(43200)?, 1, 0)
+}
+
+/// DateTime from whole Modified Julian Day number, being midnight UTC
+pub fn from_modified_julian_day_number(modified_julian_day: i64) -> Option<DateTime<FixedOffset>> {
+    let days = modified_julian_day.checked_sub(UNIX_EPOCH_MODIFIED_JULIAN_DAY_NUMBER)?;
+
+    from_ticks(days.checked_mul(86400)?, 1, 0)
+}
+
+/// Seconds from 1582-10-15 (UUID v1 and v6 epoch) to the UNIX epoch
+pub const UUID_EPOCH: i64 = -12_219_292_800;
+
+/// Milliseconds from the UNIX epoch to 2010-11-04T01:42:54.657Z, the default Twitter Snowflake epoch
+pub const TWITTER_SNOWFLAKE_EPOCH: i64 = 1_288_834_974_657;
+
+/// Crockford base32 alphabet used by ULID
+const CROCKFORD: &str = "0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Milliseconds since the UNIX epoch held by the first 10 characters of a ULID
+pub fn ulid_millis(ulid: &str) -> Option<i64> {
+    let ulid = ulid.trim();
+
+    if ulid.len() != 26 {
+        return None;
+    }
+
+    let mut value: u64 = 0;
+
+    for (i, c) in ulid.chars().enumerate() {
+        let c = match c.to_ascii_uppercase() {
+            'I' | 'L' => '1',
+            'O' => '0',
+            c => c,
+        };
+
+        let digit = CROCKFORD.find(c)? as u64;
+
+        // only the timestamp part matters, the rest just has to be valid
+        if i < 10 {
+            value = (value << 5) | digit;
+        }
+    }
+
+    // 10 characters hold 50 bits, the timestamp only 48
+    if value >> 48 != 0 {
+        return None;
+    }
+
+    Some(value as i64)
+}
+
+/// 100 nanosecond ticks since 1582-10-15 held by a UUID v1 or v6, or milliseconds since the UNIX epoch held by a UUID v7
+pub enum UuidTime {
+    Gregorian(i64),
+    UnixMillis(i64),
+}
+
+/// Decode the timestamp of a UUID in hyphenated, simple, braced or URN form
+pub fn uuid_time(uuid: &str) -> Result<UuidTime, String> {
+    let trimmed = uuid.trim();
+    let trimmed = trimmed.strip_prefix("urn:uuid:").unwrap_or(trimmed);
+    let trimmed = trimmed.strip_prefix('{').and_then(|s| s.strip_suffix('}')).unwrap_or(trimmed);
+    let hex: String = trimmed.chars().filter(|c| *c != '-').collect();
+
+    if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid UUID {}", uuid));
+    }
+
+    let value = u128::from_str_radix(&hex, 16).map_err(|_e| format!("Invalid UUID {}", uuid))?;
+    let version = (value >> 76) & 0xf;
+
+    match version {
+        1 => {
+            let time_low = (value >> 96) & 0xffff_ffff;
+            let time_mid = (value >> 80) & 0xffff;
+            let time_high = (value >> 64) & 0x0fff;
+
+            Ok(UuidTime::Gregorian(((time_high << 48) | (time_mid << 32) | time_low) as i64))
+        }
+        6 => {
+            let time_high = (value >> 96) & 0xffff_ffff;
+            let time_mid = (value >> 80) & 0xffff;
+            let time_low = (value >> 64) & 0x0fff;
+
+            Ok(UuidTime::Gregorian(((time_high << 28) | (time_mid << 12) | time_low) as i64))
+        }
+        7 => Ok(UuidTime::UnixMillis((value >> 80) as i64)),
+        _ => Err(format!(
+            "UUID {} is version {} and carries no timestamp. Supported versions are 1, 6 and 7",
+            uuid, version
+        )),
+    }
+}
+
+/// Seconds since the UNIX epoch held by the first 4 bytes of a MongoDB ObjectId
+pub fn object_id_seconds(object_id: &str) -> Option<i64> {
+    let object_id = object_id.trim();
+
+    if object_id.len() != 24 || !object_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    u32::from_str_radix(&object_id[..8], 16).ok().map(i64::from)
+}
@@ -0,0 +1,294 @@
+#![allow(unused_imports)]
+use chrono::format::{Fixed, Item, StrftimeItems};
+use chrono::{DateTime, Datelike, Days, FixedOffset, Locale, NaiveDate, NaiveTime, SecondsFormat};
+use pure_rust_locales::locale_match;
+use rhai::plugin::*;
+use rhai::{EvalAltResult, Shared};
+use std::fmt::Write;
+
+/// strftime format parsed once and reused for formatting and parsing
+#[derive(Debug, Clone)]
+pub struct CompiledFormat {
+    pub source: String,
+    pub locale: Locale,
+    pub items: Vec<Item<'static>>,
+}
+
+/// Parse a strftime format, rejecting unknown or incomplete specifiers
+pub fn compile(format: &str, locale: Locale) -> Result<CompiledFormat, Box<EvalAltResult>> {
+    let items = StrftimeItems::new_with_locale(format, locale)
+        .parse_to_owned()
+        .map_err(|e| Box::new(EvalAltResult::ErrorSystem(format!("Invalid format string {}", format), Box::new(e))))?;
+
+    Ok(CompiledFormat {
+        source: format.to_string(),
+        locale,
+        items,
+    })
+}
+
+/// Format DateTime with precompiled items, surfacing formatting failures as errors instead of panics
+pub fn format_compiled(dt: &DateTime<FixedOffset>, format: &CompiledFormat) -> Result<String, Box<EvalAltResult>> {
+    let mut output = String::new();
+
+    write!(output, "{}", dt.format_localized_with_items(format.items.iter(), format.locale))
+        .map_err(|_e| Box::<EvalAltResult>::from(format!("Failed to format DateTime using format {}", format.source)))?;
+
+    Ok(output)
+}
+
+/// Format DateTime with a localized strftime pattern, surfacing invalid patterns and formatting failures as errors instead of panics
+pub fn format_localized(dt: &DateTime<FixedOffset>, format: &str, locale: Locale) -> Result<String, Box<EvalAltResult>> {
+    format_compiled(dt, &compile(format, locale)?)
+}
+
+/// Parse fractional seconds precision name such as "millis"
+pub fn seconds_format(precision: &str) -> Result<SecondsFormat, Box<EvalAltResult>> {
+    match precision.to_lowercase().as_str() {
+        "secs" | "seconds" => Ok(SecondsFormat::Secs),
+        "millis" | "milliseconds" => Ok(SecondsFormat::Millis),
+        "micros" | "microseconds" => Ok(SecondsFormat::Micros),
+        "nanos" | "nanoseconds" => Ok(SecondsFormat::Nanos),
+        "auto" => Ok(SecondsFormat::AutoSi),
+        _ => Err(Box::<EvalAltResult>::from(format!(
+            "Invalid precision provided: {}. Supported values are secs, millis, micros, nanos and auto",
+            precision
+        ))),
+    }
+}
+
+/// Error for unknown SQL dialect names
+pub fn invalid_dialect(dialect: &str) -> Box<EvalAltResult> {
+    Box::<EvalAltResult>::from(format!(
+        "Invalid dialect provided: {}. Supported dialects are mysql, postgres, sqlite, sqlite_julian, sqlite_unix and mssql",
+        dialect
+    ))
+}
+
+fn invalid_style(style: &str) -> Box<EvalAltResult> {
+    Box::<EvalAltResult>::from(format!(
+        "Invalid style provided: {}. Supported styles are short, medium, long and full",
+        style
+    ))
+}
+
+/// Drop zero padding from day and month numbers, spelled-out dates don't use it
+fn unpadded(pattern: &str) -> String {
+    pattern.replace("%d", "%-d").replace("%e", "%-d").replace("%m", "%-m")
+}
+
+/// Medium, long and full date patterns taken from the date part of the locale's own D_T_FMT,
+/// i.e. "%a %e %b %Y" out of "%a %e %b %Y %H:%M:%S"
+fn locale_date_patterns(locale: Locale) -> [String; 3] {
+    const TIME_SPECIFIERS: [&str; 14] = ["%H", "%I", "%k", "%l", "%M", "%S", "%T", "%R", "%r", "%X", "%p", "%P", "%Z", "%z"];
+
+    let d_t_fmt: &str = locale_match!(locale => LC_TIME::D_T_FMT);
+    let date: Vec<&str> = d_t_fmt
+        .split_whitespace()
+        .filter(|token| !TIME_SPECIFIERS.iter().any(|specifier| token.contains(specifier)))
+        .collect();
+
+    let is_weekday = |token: &&str| token.contains("%a") || token.contains("%A");
+    let join = |tokens: Vec<&str>| unpadded(tokens.join(" ").trim_end_matches([',', ' '])).replace("%h", "%b");
+    // the dot of an abbreviated month name goes with the abbreviation, i.e. "aug." but "augusztus"
+    let spelled_out = |pattern: String| pattern.replace("%b.", "%B").replace("%b", "%B");
+
+    let medium = join(date.iter().filter(|token| !is_weekday(token)).copied().collect());
+
+    if medium.is_empty() {
+        return ["%x".to_string(), "%x".to_string(), "%A %x".to_string()];
+    }
+
+    let long = spelled_out(medium.clone());
+    let full = if date.iter().any(is_weekday) {
+        spelled_out(join(date).replace("%a", "%A"))
+    } else {
+        format!("%A {}", long)
+    };
+
+    [medium, long, full]
+}
+
+/// The locale's own T_FMT with the composite %T, %R and %r specifiers spelled out
+fn locale_time_pattern(locale: Locale) -> String {
+    let t_fmt: &str = locale_match!(locale => LC_TIME::T_FMT);
+    let t_fmt_ampm: &str = locale_match!(locale => LC_TIME::T_FMT_AMPM);
+
+    t_fmt.replace("%T", "%H:%M:%S").replace("%R", "%H:%M").replace("%r", t_fmt_ampm)
+}
+
+/// Drop the seconds and their separator or unit from a time pattern,
+/// i.e. "%I:%M %p" out of "%I:%M:%S %p" and "%H时%M分" out of "%H时%M分%S秒"
+fn without_seconds(pattern: &str) -> String {
+    let Some(index) = pattern.find("%S") else {
+        return pattern.to_string();
+    };
+
+    let rest = &pattern[index + 2..];
+    let end = index + 2 + rest.find(|c: char| c == '%' || c.is_whitespace()).unwrap_or(rest.len());
+
+    let before = &pattern[..index];
+    let start = before.strip_suffix([':', '.']).unwrap_or(before.trim_end()).len();
+
+    format!("{}{}", &pattern[..start], &pattern[end..])
+}
+
+/// strftime pattern for the date style, "short" being the locale's own D_FMT and the others derived from its D_T_FMT
+pub fn date_pattern(style: &str, locale: Locale) -> Result<String, Box<EvalAltResult>> {
+    let index = match style.to_lowercase().as_str() {
+        "short" => return Ok("%x".to_string()),
+        "medium" => 0,
+        "long" => 1,
+        "full" => 2,
+        _ => return Err(invalid_style(style)),
+    };
+
+    Ok(locale_date_patterns(locale)[index].clone())
+}
+
+/// strftime pattern for the time style, derived from the locale's T_FMT
+pub fn time_pattern(style: &str, locale: Locale) -> Result<String, Box<EvalAltResult>> {
+    let t_fmt = locale_time_pattern(locale);
+
+    match style.to_lowercase().as_str() {
+        "short" => Ok(without_seconds(&t_fmt)),
+        "medium" => Ok(t_fmt),
+        "long" | "full" => Ok(format!("{} %:z", t_fmt)),
+        _ => Err(invalid_style(style)),
+    }
+}
+
+/// strftime pattern for the combined date and time style
+pub fn datetime_pattern(style: &str, locale: Locale) -> Result<String, Box<EvalAltResult>> {
+    Ok(format!("{} {}", date_pattern(style, locale)?, time_pattern(style, locale)?))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum NameKind {
+    Month,
+    Weekday,
+}
+
+/// Localized full and abbreviated names, lowercase, paired with the English full and abbreviated names chrono parses
+fn localized_names(kind: NameKind, locale: Locale) -> Vec<(String, String, String)> {
+    // 2001-01-01 is a Monday
+    let first = NaiveDate::from_ymd_opt(2001, 1, 1).unwrap();
+
+    let dates: Vec<NaiveDate> = match kind {
+        NameKind::Month => (1..=12).filter_map(|m| first.with_month(m)).collect(),
+        NameKind::Weekday => (0..7).filter_map(|d| first.checked_add_days(Days::new(d))).collect(),
+    };
+
+    let (long, short) = match kind {
+        NameKind::Month => ("%B", "%b"),
+        NameKind::Weekday => ("%A", "%a"),
+    };
+
+    let mut names: Vec<(String, String, String)> = Vec::new();
+
+    for date in dates {
+        let english_long = date.format(long).to_string();
+        let english_short = date.format(short).to_string();
+
+        for pattern in [long, short] {
+            let mut name = String::new();
+
+            if write!(name, "{}", date.format_localized(pattern, locale)).is_ok() && !name.trim().is_empty() {
+                names.push((name.trim().to_lowercase(), english_long.clone(), english_short.clone()));
+            }
+        }
+    }
+
+    // longest first so "novembre" wins over "nov."
+    names.sort_by_key(|(name, _, _)| std::cmp::Reverse(name.chars().count()));
+
+    names
+}
+
+/// Replace localized month and weekday names in a timestamp with their English equivalents
+///
+/// Names are matched case-insensitively in the order the format expects them, so a word that is both a month and a weekday
+/// abbreviation (i.e. "mar" in Spanish) is resolved by its position in the format.
+pub fn delocalize(timestamp: &str, format: &str, locale: Locale) -> String {
+    // chrono only accepts abbreviated English names for %a and %b, so remember which form each specifier needs
+    let expected: Vec<(NameKind, bool)> = StrftimeItems::new(format)
+        .filter_map(|item| match item {
+            Item::Fixed(Fixed::LongMonthName) => Some((NameKind::Month, true)),
+            Item::Fixed(Fixed::ShortMonthName) => Some((NameKind::Month, false)),
+            Item::Fixed(Fixed::LongWeekdayName) => Some((NameKind::Weekday, true)),
+            Item::Fixed(Fixed::ShortWeekdayName) => Some((NameKind::Weekday, false)),
+            _ => None,
+        })
+        .collect();
+
+    let months = localized_names(NameKind::Month, locale);
+    let weekdays = localized_names(NameKind::Weekday, locale);
+
+    let chars: Vec<char> = timestamp.chars().collect();
+    let mut expected = expected.into_iter().peekable();
+    let mut output = String::with_capacity(timestamp.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let Some((kind, long)) = expected.peek() else {
+            output.extend(&chars[i..]);
+            break;
+        };
+
+        let names = if *kind == NameKind::Month { &months } else { &weekdays };
+        let at_boundary = i == 0 || !chars[i - 1].is_alphabetic();
+
+        let found = names.iter().find(|(name, _, _)| {
+            let len = name.chars().count();
+
+            if i + len > chars.len() {
+                return false;
+            }
+
+            let candidate: String = chars[i..i + len].iter().collect::<String>().to_lowercase();
+            let starts_word = !name.starts_with(char::is_alphabetic) || at_boundary;
+            let ends_word = !name.ends_with(char::is_alphabetic) || chars.get(i + len).is_none_or(|c| !c.is_alphabetic());
+
+            candidate == *name && starts_word && ends_word
+        });
+
+        if let Some((name, english_long, english_short)) = found {
+            output.push_str(if *long { english_long } else { english_short });
+            i += name.chars().count();
+            expected.next();
+        } else {
+            output.push(chars[i]);
+            i += 1;
+        }
+    }
+
+    output
+}
+
+#[export_module]
+pub mod format_module {
+    use chrono::Locale;
+
+    use rhai::{EvalAltResult, Position, Shared};
+
+    /// Alias type to bridge rhai and a precompiled strftime format
+    pub type DateTimeFormat = Shared<CompiledFormat>;
+
+    /// Construct a reusable format from a strftime format string
+    #[rhai_fn(return_raw, name = "format_compile")]
+    pub fn format_compile(format: &str) -> Result<DateTimeFormat, Box<EvalAltResult>> {
+        compile(format, Locale::POSIX).map(Shared::new)
+    }
+
+    /// Construct a reusable format from a strftime format string and locale
+    #[rhai_fn(return_raw, name = "format_compile")]
+    pub fn format_compile_localized(format: &str, locale: &str) -> Result<DateTimeFormat, Box<EvalAltResult>> {
+        compile(format, crate::calendar::parse_locale(locale)?).map(Shared::new)
+    }
+
+    /// Output the source format string
+    #[rhai_fn(global, name = "to_string", pure)]
+    pub fn to_string(format: &mut DateTimeFormat) -> ImmutableString {
+        format.source.clone().into()
+    }
+}
@@ -0,0 +1,317 @@
+#![allow(unused_imports)]
+use chrono::{DateTime, FixedOffset, TimeDelta};
+use rhai::plugin::*;
+use rhai::{Locked, Shared};
+use std::ops::DerefMut;
+
+/// Half-open time window `[start, end)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTimeInterval {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+}
+
+impl DateTimeInterval {
+    /// Check if the interval covers no time at all
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// Check if a point in time falls within `[start, end)`
+    pub fn contains(&self, dt: &DateTime<FixedOffset>) -> bool {
+        self.start <= *dt && *dt < self.end
+    }
+
+    /// Check if two intervals share any time
+    pub fn overlaps(&self, other: &DateTimeInterval) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// Check if two intervals overlap or touch end to start
+    pub fn touches(&self, other: &DateTimeInterval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+}
+
+#[inline(always)]
+pub fn borrow_mut(interval: &Shared<Locked<DateTimeInterval>>) -> impl DerefMut<Target = DateTimeInterval> + '_ {
+    #[cfg(not(feature = "sync"))]
+    return interval.borrow_mut();
+
+    #[cfg(feature = "sync")]
+    return interval.write().unwrap();
+}
+
+/// Sort by start and merge intervals that overlap or touch
+pub fn merge(mut intervals: Vec<DateTimeInterval>) -> Vec<DateTimeInterval> {
+    intervals.retain(|i| !i.is_empty());
+    intervals.sort_by_key(|i| i.start);
+
+    let mut merged: Vec<DateTimeInterval> = Vec::with_capacity(intervals.len());
+
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if last.touches(&interval) => {
+                if interval.end > last.end {
+                    last.end = interval.end;
+                }
+            }
+            _ => merged.push(interval),
+        }
+    }
+
+    merged
+}
+
+/// Remove every moment covered by `holes` from `intervals`
+pub fn subtract(intervals: Vec<DateTimeInterval>, holes: Vec<DateTimeInterval>) -> Vec<DateTimeInterval> {
+    let holes = merge(holes);
+    let mut result = Vec::new();
+
+    for interval in merge(intervals) {
+        let mut rest = interval;
+
+        for hole in holes.iter().filter(|h| h.overlaps(&interval)) {
+            if hole.start > rest.start {
+                result.push(DateTimeInterval {
+                    start: rest.start,
+                    end: hole.start,
+                });
+            }
+
+            if hole.end > rest.start {
+                rest.start = hole.end;
+            }
+        }
+
+        if !rest.is_empty() {
+            result.push(rest);
+        }
+    }
+
+    result
+}
+
+#[export_module]
+pub mod interval_module {
+    use chrono::DateTime;
+    use chrono::FixedOffset;
+    use chrono::TimeDelta;
+
+    use rhai::{Array, Dynamic, EvalAltResult, Locked, Position, Shared};
+
+    use crate::datetime::datetime_module::DateTimeFixed;
+    use crate::timedelta::timedelta_module::Timedelta;
+
+    /// Alias type to bridge rhai and a half-open DateTime window
+    pub type Interval = Shared<Locked<DateTimeInterval>>;
+
+    fn wrap(interval: DateTimeInterval) -> Interval {
+        Shared::new(Locked::new(interval))
+    }
+
+    fn to_vec(intervals: Array) -> Result<Vec<DateTimeInterval>, Box<EvalAltResult>> {
+        intervals
+            .into_iter()
+            .map(|item| {
+                item.try_cast::<Interval>()
+                    .map(|i| *borrow_mut(&i))
+                    .ok_or(Box::<EvalAltResult>::from("Array must only contain intervals".to_string()))
+            })
+            .collect()
+    }
+
+    fn to_array(intervals: Vec<DateTimeInterval>) -> Array {
+        intervals.into_iter().map(|i| Dynamic::from(wrap(i))).collect()
+    }
+
+    /// Construct Interval from start (inclusive) and end (exclusive) DateTime
+    #[rhai_fn(return_raw, name = "interval")]
+    pub fn interval(start: DateTimeFixed, end: DateTimeFixed) -> Result<Interval, Box<EvalAltResult>> {
+        let start = *crate::datetime::borrow_mut(&start);
+        let end = *crate::datetime::borrow_mut(&end);
+
+        if end < start {
+            return Err(Box::<EvalAltResult>::from("Interval end must not be before its start".to_string()));
+        }
+
+        Ok(wrap(DateTimeInterval { start, end }))
+    }
+
+    /// Construct Interval from start DateTime and Timedelta length
+    #[rhai_fn(return_raw, name = "interval")]
+    pub fn interval_with_duration(start: DateTimeFixed, duration: Timedelta) -> Result<Interval, Box<EvalAltResult>> {
+        let start = *crate::datetime::borrow_mut(&start);
+        let duration = *crate::timedelta::borrow_mut(&duration);
+
+        if duration < TimeDelta::zero() {
+            return Err(Box::<EvalAltResult>::from("Interval duration must not be negative".to_string()));
+        }
+
+        let end = start.checked_add_signed(duration).ok_or(Box::<EvalAltResult>::from(
+            "TimeDelta results in DateTime out of range or doesn't make any sense.".to_string(),
+        ))?;
+
+        Ok(wrap(DateTimeInterval { start, end }))
+    }
+
+    /// Output ISO 8601 interval string
+    #[rhai_fn(global, name = "to_string", pure)]
+    pub fn to_string(interval: &mut Interval) -> ImmutableString {
+        let this = borrow_mut(interval);
+
+        format!("{}/{}", this.start.to_rfc3339(), this.end.to_rfc3339()).into()
+    }
+
+    /// Get the start
+    #[rhai_fn(global, get = "start", name = "start", name = "get_start", pure)]
+    pub fn get_start(interval: &mut Interval) -> DateTimeFixed {
+        Shared::new(Locked::new(borrow_mut(interval).start))
+    }
+
+    /// Get the end
+    #[rhai_fn(global, get = "end", name = "end", name = "get_end", pure)]
+    pub fn get_end(interval: &mut Interval) -> DateTimeFixed {
+        Shared::new(Locked::new(borrow_mut(interval).end))
+    }
+
+    /// Check if is empty
+    #[rhai_fn(global, get = "is_empty", name = "is_empty", pure)]
+    pub fn is_empty(interval: &mut Interval) -> bool {
+        borrow_mut(interval).is_empty()
+    }
+
+    /// Get the length as Timedelta
+    #[rhai_fn(global, get = "duration", name = "duration", name = "get_duration", pure)]
+    pub fn duration(interval: &mut Interval) -> Timedelta {
+        let this = borrow_mut(interval);
+
+        Shared::new(Locked::new(this.end.signed_duration_since(this.start)))
+    }
+
+    /// Check if DateTime is within the interval
+    #[rhai_fn(global, name = "contains", pure)]
+    pub fn contains(interval: &mut Interval, dt: DateTimeFixed) -> bool {
+        let dt = *crate::datetime::borrow_mut(&dt);
+
+        borrow_mut(interval).contains(&dt)
+    }
+
+    /// Check if another interval is fully within the interval
+    #[rhai_fn(global, name = "contains", pure)]
+    pub fn contains_interval(interval: &mut Interval, other: Interval) -> bool {
+        let this = *borrow_mut(interval);
+        let other = *borrow_mut(&other);
+
+        this.start <= other.start && other.end <= this.end
+    }
+
+    /// Check if two intervals overlap
+    #[rhai_fn(global, name = "overlaps", pure)]
+    pub fn overlaps(interval: &mut Interval, other: Interval) -> bool {
+        let this = *borrow_mut(interval);
+        let other = *borrow_mut(&other);
+
+        this.overlaps(&other)
+    }
+
+    /// Overlapping part of two intervals or unit if they don't overlap
+    #[rhai_fn(global, name = "intersection", name = "intersect", pure)]
+    pub fn intersection(interval: &mut Interval, other: Interval) -> Dynamic {
+        let this = *borrow_mut(interval);
+        let other = *borrow_mut(&other);
+
+        if !this.overlaps(&other) {
+            return Dynamic::UNIT;
+        }
+
+        Dynamic::from(wrap(DateTimeInterval {
+            start: this.start.max(other.start),
+            end: this.end.min(other.end),
+        }))
+    }
+
+    /// Combined interval of two overlapping or adjacent intervals or unit if there is a gap between them
+    #[rhai_fn(global, name = "union", pure)]
+    pub fn union(interval: &mut Interval, other: Interval) -> Dynamic {
+        let this = *borrow_mut(interval);
+        let other = *borrow_mut(&other);
+
+        if !this.touches(&other) {
+            return Dynamic::UNIT;
+        }
+
+        Dynamic::from(wrap(DateTimeInterval {
+            start: this.start.min(other.start),
+            end: this.end.max(other.end),
+        }))
+    }
+
+    /// Interval between two non-overlapping intervals or unit if they overlap or touch
+    #[rhai_fn(global, name = "gap", pure)]
+    pub fn gap(interval: &mut Interval, other: Interval) -> Dynamic {
+        let this = *borrow_mut(interval);
+        let other = *borrow_mut(&other);
+
+        if this.touches(&other) {
+            return Dynamic::UNIT;
+        }
+
+        Dynamic::from(wrap(DateTimeInterval {
+            start: this.end.min(other.end),
+            end: this.start.max(other.start),
+        }))
+    }
+
+    /// Split into consecutive intervals of given length; the last one may be shorter
+    #[rhai_fn(global, name = "split_by", pure, return_raw)]
+    pub fn split_by(interval: &mut Interval, step: Timedelta) -> Result<Array, Box<EvalAltResult>> {
+        let this = *borrow_mut(interval);
+        let step = *crate::timedelta::borrow_mut(&step);
+
+        if step <= TimeDelta::zero() {
+            return Err(Box::<EvalAltResult>::from("Step must be a positive Timedelta".to_string()));
+        }
+
+        let mut parts = Vec::new();
+        let mut start = this.start;
+
+        while start < this.end {
+            let end = start.checked_add_signed(step).map_or(this.end, |end| end.min(this.end));
+
+            parts.push(DateTimeInterval { start, end });
+
+            start = end;
+        }
+
+        Ok(to_array(parts))
+    }
+
+    /// Move both start and end by Timedelta
+    #[rhai_fn(global, name = "shift", pure, return_raw)]
+    pub fn shift(interval: &mut Interval, td: Timedelta) -> Result<(), Box<EvalAltResult>> {
+        let mut this = borrow_mut(interval);
+        let td = *crate::timedelta::borrow_mut(&td);
+
+        let error = || Box::<EvalAltResult>::from("TimeDelta results in DateTime out of range or doesn't make any sense.".to_string());
+
+        let start = this.start.checked_add_signed(td).ok_or_else(error)?;
+        let end = this.end.checked_add_signed(td).ok_or_else(error)?;
+
+        *this = DateTimeInterval { start, end };
+
+        Ok(())
+    }
+
+    /// Sort an array of intervals and merge the ones that overlap or touch
+    #[rhai_fn(name = "merge_intervals", return_raw)]
+    pub fn merge_intervals(intervals: Array) -> Result<Array, Box<EvalAltResult>> {
+        Ok(to_array(merge(to_vec(intervals)?)))
+    }
+
+    /// Remove the time covered by one array of intervals from another
+    #[rhai_fn(name = "subtract_intervals", return_raw)]
+    pub fn subtract_intervals(intervals: Array, holes: Array) -> Result<Array, Box<EvalAltResult>> {
+        Ok(to_array(subtract(to_vec(intervals)?, to_vec(holes)?)))
+    }
+}
@@ -1,7 +1,13 @@
 use rhai::def_package;
 use rhai::plugin::*;
 
+pub(crate) mod aggregate;
+pub(crate) mod calendar;
 pub(crate) mod datetime;
+pub(crate) mod epoch;
+pub(crate) mod format;
+pub(crate) mod interval;
+pub(crate) mod relative;
 pub(crate) mod timedelta;
 
 def_package! {
@@ -9,6 +15,10 @@ def_package! {
     pub ChronoPackage(lib) {
        combine_with_exported_module!(lib, "rhai_chrono_datetime", datetime::datetime_module);
        combine_with_exported_module!(lib, "rhai_chrono_timedelta", timedelta::timedelta_module);
+       combine_with_exported_module!(lib, "rhai_chrono_interval", interval::interval_module);
+       combine_with_exported_module!(lib, "rhai_chrono_calendar", calendar::calendar_module);
+       combine_with_exported_module!(lib, "rhai_chrono_format", format::format_module);
+       combine_with_exported_module!(lib, "rhai_chrono_aggregate", aggregate::aggregate_module);
     }
 }
 
@@ -275,11 +285,11 @@ mod tests {
                     timestamp_rfc3339_nanos
                 ))
                 .unwrap_or_default(),
-            (years_since * -1i32) as rhai::INT,
+            -years_since as rhai::INT,
             "we should be getting number of years"
         );
 
-        assert_eq!(
+        assert!(
             engine
                 .eval::<rhai::INT>(&format!(
                     r#"let dt = datetime_parse("2111-03-05 11:25:00", "{}"); dt.years_since()"#,
@@ -287,7 +297,6 @@ mod tests {
                 ))
                 .unwrap_or_default()
                 > 10,
-            true,
             "we should be getting number of years"
         );
 
@@ -298,7 +307,7 @@ mod tests {
                     timestamp_rfc3339_nanos
                 ))
                 .unwrap_or_default(),
-            (years_since * -1i32) as rhai::INT,
+            -years_since as rhai::INT,
             "we should be getting number of years"
         );
 
@@ -862,33 +871,28 @@ mod tests {
 
         // test init zero
         assert_eq!(
-            engine
-                .eval::<rhai::INT>(r#"let td = timedelta_zero(); td.seconds"#)
-                .unwrap_or_default(),
+            engine.eval::<rhai::INT>(r#"let td = timedelta_zero(); td.seconds"#).unwrap(),
             0 as rhai::INT,
             "we should be getting zero timedelta"
         );
 
         // test init zero
-        assert_eq!(
+        assert!(
             engine
                 .eval::<bool>(r#"let td = timedelta_zero(); td.is_zero()"#)
                 .unwrap_or_default(),
-            true,
             "we should be getting zero timedelta"
         );
 
         // test init min
-        assert_eq!(
-            engine.eval::<bool>(r#"let td = timedelta_min(); td.is_zero()"#).unwrap_or_default(),
-            false,
+        assert!(
+            !engine.eval::<bool>(r#"let td = timedelta_min(); td.is_zero()"#).unwrap(),
             "we should be getting min timedelta"
         );
 
         // test init max
-        assert_eq!(
-            engine.eval::<bool>(r#"let td = timedelta_max(); td.is_zero()"#).unwrap_or_default(),
-            false,
+        assert!(
+            !engine.eval::<bool>(r#"let td = timedelta_max(); td.is_zero()"#).unwrap(),
             "we should be getting max timedelta"
         );
 
@@ -985,7 +989,7 @@ mod tests {
         // test abs
         assert_eq!(
             engine
-                .eval::<rhai::INT>(r#"let td = timedelta_weeks(-4); td.abs(); td.seconds"#)
+                .eval::<rhai::INT>(r#"let td = timedelta_weeks(-4); td.abs().seconds"#)
                 .unwrap_or_default(),
             28 * 86400 as rhai::INT,
             "we should be getting 4 weeks worth of seconds"
@@ -1087,4 +1091,2211 @@ mod tests {
             "we should be getting parse error"
         );
     }
+
+    #[test]
+    fn interval_works() {
+        let engine = get_engine();
+
+        // test contains is half-open
+        assert!(
+            engine
+                .eval::<bool>(
+                    r#"
+                let i = interval(datetime_unix(0), datetime_unix(3600));
+
+                i.contains(datetime_unix(0)) && i.contains(datetime_unix(3599)) && !i.contains(datetime_unix(3600))
+            "#
+                )
+                .unwrap_or_default(),
+            "we should be including start and excluding end"
+        );
+
+        // test duration
+        assert_eq!(
+            engine
+                .eval::<rhai::INT>(r#"let i = interval(datetime_unix(0), timedelta_hours(2)); i.duration.seconds"#)
+                .unwrap_or_default(),
+            7200 as rhai::INT,
+            "we should be getting 7200"
+        );
+
+        // test overlaps
+        assert!(
+            !engine
+                .eval::<bool>(
+                    r#"
+                let a = interval(datetime_unix(0), datetime_unix(100));
+                let b = interval(datetime_unix(100), datetime_unix(200));
+
+                a.overlaps(b)
+            "#
+                )
+                .unwrap(),
+            "adjacent intervals should not overlap"
+        );
+
+        // test intersection
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let a = interval(datetime_unix(0), datetime_unix(100));
+                let b = interval(datetime_unix(50), datetime_unix(200));
+
+                a.intersection(b).to_string()
+            "#
+                )
+                .unwrap_or_default(),
+            "1970-01-01T00:00:50+00:00/1970-01-01T00:01:40+00:00",
+            "we should be getting the overlapping part"
+        );
+
+        // test union and gap
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let a = interval(datetime_unix(0), datetime_unix(100));
+                let b = interval(datetime_unix(100), datetime_unix(200));
+                let c = interval(datetime_unix(300), datetime_unix(400));
+
+                `${a.union(b)} ${a.union(c) == ()} ${b.gap(c)} ${a.gap(b) == ()}`
+            "#
+                )
+                .unwrap_or_default(),
+            "1970-01-01T00:00:00+00:00/1970-01-01T00:03:20+00:00 true 1970-01-01T00:03:20+00:00/1970-01-01T00:05:00+00:00 true",
+            "we should be getting union and gap"
+        );
+
+        // test split_by
+        assert_eq!(
+            engine
+                .eval::<rhai::INT>(
+                    r#"
+                let parts = interval(datetime_unix(0), datetime_unix(250)).split_by(timedelta_seconds(100));
+
+                parts.len() * 1000 + parts[2].duration.seconds
+            "#
+                )
+                .unwrap_or_default(),
+            3050 as rhai::INT,
+            "we should be getting 3 parts with a shorter tail"
+        );
+
+        // test shift
+        assert_eq!(
+            engine
+                .eval::<rhai::INT>(
+                    r#"
+                let i = interval(datetime_unix(0), datetime_unix(100));
+                i.shift(timedelta_minutes(1));
+
+                i.start.timestamp()
+            "#
+                )
+                .unwrap_or_default(),
+            60 as rhai::INT,
+            "we should be getting 60"
+        );
+
+        // test merge_intervals
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let merged = merge_intervals([
+                    interval(datetime_unix(300), datetime_unix(400)),
+                    interval(datetime_unix(0), datetime_unix(100)),
+                    interval(datetime_unix(50), datetime_unix(150)),
+                    interval(datetime_unix(150), datetime_unix(200)),
+                ]);
+
+                merged.map(|i| `${i.start.timestamp()}-${i.end.timestamp()}`).reduce(|sum, s| if sum == () { s } else { sum + " " + s })
+            "#
+                )
+                .unwrap_or_default(),
+            "0-200 300-400",
+            "we should be getting merged intervals"
+        );
+
+        // test subtract_intervals
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let rest = subtract_intervals(
+                    [interval(datetime_unix(0), datetime_unix(1000))],
+                    [interval(datetime_unix(100), datetime_unix(200)), interval(datetime_unix(900), datetime_unix(1100))]
+                );
+
+                rest.map(|i| `${i.start.timestamp()}-${i.end.timestamp()}`).reduce(|sum, s| if sum == () { s } else { sum + " " + s })
+            "#
+                )
+                .unwrap_or_default(),
+            "0-100 200-900",
+            "we should be getting what's left"
+        );
+
+        // test reversed bounds
+        assert!(
+            engine
+                .eval::<rhai::Dynamic>(r#"interval(datetime_unix(100), datetime_unix(0))"#)
+                .is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn calendar_works() {
+        let engine = get_engine();
+
+        // test number of weeks
+        assert_eq!(
+            engine
+                .eval::<rhai::INT>(r#"let weeks = calendar_month(2024, 2, "mon"); weeks.len()"#)
+                .unwrap_or_default(),
+            5 as rhai::INT,
+            "we should be getting 5 weeks"
+        );
+
+        // test leading days
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let first = calendar_month(2024, 2, "mon")[0];
+
+                `${first[0].day} ${first[0].in_month} ${first[3].day} ${first[3].in_month} ${first[3].weekday_name}`
+            "#
+                )
+                .unwrap_or_default(),
+            "29 false 1 true Thursday",
+            "we should be getting Jan 29th as first cell and Feb 1st on Thursday"
+        );
+
+        // test sunday week start and trailing days
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let weeks = calendar_month(2024, 2, "sunday");
+                let last = weeks[weeks.len() - 1];
+
+                `${weeks[0][0].day} ${last[6].day} ${last[6].month} ${last[6].iso_week}`
+            "#
+                )
+                .unwrap_or_default(),
+            "28 2 3 9",
+            "we should be getting Jan 28th first and Mar 2nd last"
+        );
+
+        // test date entry
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let weeks = calendar_month(1989, 8, "mon"); weeks[1][2].date.to_string()"#)
+                .unwrap_or_default(),
+            "1989-08-09T00:00:00+00:00",
+            "we should be getting RFC3339 string"
+        );
+
+        // test localized
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let weeks = calendar_month(1989, 8, "mon", "fr_FR");
+
+                `${weeks[1][2].weekday_name} ${weeks[1][2].month_name}`
+            "#
+                )
+                .unwrap_or_default(),
+            "mercredi août",
+            "we should be getting pretty french words"
+        );
+
+        // test weekday header
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let names = calendar_weekdays("sun"); `${names[0]} ${names[6]}`"#)
+                .unwrap_or_default(),
+            "Sunday Saturday",
+            "we should be getting Sunday first"
+        );
+
+        // test invalid month
+        assert!(
+            engine.eval::<rhai::Array>(r#"calendar_month(2024, 13, "mon")"#).is_err(),
+            "we should be getting an error"
+        );
+
+        // test values that would wrap into range when narrowed
+        for script in [
+            r#"calendar_month(2024, 4294967297, "mon")"#,
+            r#"calendar_month(4294969320, 1, "mon")"#,
+            r#"calendar_month(4294969320, 1, "mon", "fr_FR")"#,
+        ] {
+            assert!(
+                engine.eval::<rhai::Array>(script).is_err(),
+                "we should be getting an error from {}",
+                script
+            );
+        }
+
+        // test invalid week start
+        assert!(
+            engine.eval::<rhai::Array>(r#"calendar_month(2024, 1, "someday")"#).is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn fiscal_works() {
+        let engine = get_engine();
+
+        // test quarter and half
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("1989-08-09T09:30:11+00:00"); `Q${dt.quarter} H${dt.half}`"#)
+                .unwrap_or_default(),
+            "Q3 H2",
+            "we should be getting Q3 H2"
+        );
+
+        // test start_of
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("1989-08-09T09:30:11+02:00"); dt.start_of("quarter"); dt.to_string()"#)
+                .unwrap_or_default(),
+            "1989-07-01T00:00:00+02:00",
+            "we should be getting start of Q3 in the same offset"
+        );
+
+        // test start_of week
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("1989-08-09T09:30:11+00:00"); dt.start_of("week"); dt.to_string()"#)
+                .unwrap_or_default(),
+            "1989-08-07T00:00:00+00:00",
+            "we should be getting Monday"
+        );
+
+        // test end_of
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("1989-08-09T09:30:11+00:00"); dt.end_of("month"); dt.to_string()"#)
+                .unwrap_or_default(),
+            "1989-08-31T23:59:59.999999999+00:00",
+            "we should be getting the last nanosecond of August"
+        );
+
+        // test fiscal year and quarter
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"let dt = datetime_rfc3339("2024-11-15T12:00:00+00:00"); `Q${dt.fiscal_quarter(4)} FY${dt.fiscal_year(4)}`"#
+                )
+                .unwrap_or_default(),
+            "Q3 FY2025",
+            "we should be getting Q3 FY2025"
+        );
+
+        // test calendar fiscal year
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"let dt = datetime_rfc3339("2024-11-15T12:00:00+00:00"); `Q${dt.fiscal_quarter(1)} FY${dt.fiscal_year(1)}`"#
+                )
+                .unwrap_or_default(),
+            "Q4 FY2024",
+            "we should be getting Q4 FY2024"
+        );
+
+        // test fiscal year bounds
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let dt = datetime_rfc3339("2025-02-15T12:00:00+00:00");
+
+                `${dt.fiscal_year_start(4)} ${dt.fiscal_year_end(4)}`
+            "#
+                )
+                .unwrap_or_default(),
+            "2024-04-01T00:00:00+00:00 2025-03-31T23:59:59.999999999+00:00",
+            "we should be getting April to March"
+        );
+
+        // test 4-5-4 retail calendar with 53 weeks
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let dt = datetime_rfc3339("2023-12-25T12:00:00+00:00");
+                let p = dt.retail_period(1, "sat", "nearest", "454");
+
+                `${p.fiscal_year} ${p.weeks_in_year} ${p.week} ${p.period} ${p.quarter} ${p.year_start} ${p.year_end}`
+            "#
+                )
+                .unwrap_or_default(),
+            "2024 53 48 11 4 2023-01-29T00:00:00+00:00 2024-02-03T23:59:59.999999999+00:00",
+            "we should be getting week 48 of a 53 week year"
+        );
+
+        // test 4-4-5 retail calendar
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let dt = datetime_rfc3339("2024-03-01T12:00:00+00:00");
+                let p = dt.retail_period(12, "sun", "445");
+
+                `${p.fiscal_year} ${p.weeks_in_year} ${p.week} ${p.period}`
+            "#
+                )
+                .unwrap_or_default(),
+            "2024 52 9 3",
+            "we should be getting week 9 in period 3"
+        );
+
+        // test bad input
+        assert!(
+            engine.eval::<rhai::INT>(r#"let dt = datetime_now(); dt.fiscal_year(13)"#).is_err(),
+            "we should be getting an error"
+        );
+
+        assert!(
+            engine.eval::<()>(r#"let dt = datetime_now(); dt.start_of("fortnight")"#).is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn calendar_facts_work() {
+        let engine = get_engine();
+
+        // test leap years
+        assert_eq!(
+            engine
+                .eval::<String>(r#"`${is_leap_year(2024)} ${is_leap_year(1900)} ${is_leap_year(2000)} ${is_leap_year(2023)}`"#)
+                .unwrap_or_default(),
+            "true false true false",
+            "we should be following gregorian rules"
+        );
+
+        // test days in month
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"`${days_in_month(2024, 2)} ${days_in_month(2023, 2)} ${days_in_month(2023, 4)} ${days_in_month(2023, 12)}`"#
+                )
+                .unwrap_or_default(),
+            "29 28 30 31",
+            "we should be getting days in month"
+        );
+
+        // test days in year
+        assert_eq!(
+            engine
+                .eval::<String>(r#"`${days_in_year(2024)} ${days_in_year(2023)}`"#)
+                .unwrap_or_default(),
+            "366 365",
+            "we should be getting days in year"
+        );
+
+        // test weeks in iso year
+        assert_eq!(
+            engine
+                .eval::<String>(r#"`${weeks_in_iso_year(2020)} ${weeks_in_iso_year(2021)} ${weeks_in_iso_year(2026)}`"#)
+                .unwrap_or_default(),
+            "53 52 53",
+            "we should be getting ISO weeks in year"
+        );
+
+        // test valid dates
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"`${is_valid_date(2024, 2, 29)} ${is_valid_date(2023, 2, 29)} ${is_valid_date(2023, 13, 1)} ${is_valid_date(2023, -1, 1)}`"#
+                )
+                .unwrap_or_default(),
+            "true false false false",
+            "we should be validating dates"
+        );
+
+        // test instance getters
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("2024-02-10T09:30:11+00:00"); `${dt.days_in_month} ${dt.is_leap_year}`"#)
+                .unwrap_or_default(),
+            "29 true",
+            "we should be getting 29 days in a leap year February"
+        );
+
+        // test invalid month
+        assert!(
+            engine.eval::<rhai::INT>(r#"days_in_month(2024, 0)"#).is_err(),
+            "we should be getting an error"
+        );
+
+        // test invalid year
+        assert!(
+            engine.eval::<rhai::INT>(r#"days_in_year(1000000000)"#).is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn format_style_works() {
+        let engine = get_engine();
+
+        let timestamp_rfc3339 = "1989-08-09T09:30:11+00:00";
+
+        // test date styles
+        for (style, locale, expected) in [
+            ("short", "en_US", "08/09/1989"),
+            ("medium", "en_US", "9 Aug 1989"),
+            ("long", "en_US", "9 August 1989"),
+            ("full", "en_US", "Wednesday 9 August 1989"),
+            ("medium", "POSIX", "Aug 9 1989"),
+            ("short", "fr_FR", "09/08/1989"),
+            ("medium", "fr_FR", "9 août 1989"),
+            ("long", "fr_FR", "9 août 1989"),
+            ("full", "fr_FR", "mercredi 9 août 1989"),
+            ("short", "de_DE", "09.08.1989"),
+            ("full", "de_DE", "Mittwoch 9 August 1989"),
+            ("long", "sv_SE", "9 augusti 1989"),
+            ("medium", "hu_HU", "1989. aug. 9."),
+            ("long", "hu_HU", "1989. augusztus 9."),
+            ("full", "hu_HU", "1989. augusztus 9., szerda"),
+            ("short", "zh_CN", "1989年08月09日"),
+            ("long", "zh_CN", "1989年8月9日"),
+            ("full", "ko_KR", "1989년 8월 9일 (수요일)"),
+            ("full", "ja_JP", "水曜日 1989年8月9日"),
+            ("long", "ru_RU", "9 августа 1989"),
+            ("full", "pl_PL", "środa, 9 sierpnia 1989"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(
+                        r#"let dt = datetime_rfc3339("{}"); dt.format_date_style("{}", "{}")"#,
+                        timestamp_rfc3339, style, locale
+                    ))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} {} date",
+                style,
+                locale
+            );
+        }
+
+        // test time styles
+        for (style, locale, expected) in [
+            ("short", "en_US", "09:30 AM"),
+            ("medium", "en_US", "09:30:11 AM"),
+            ("short", "fr_FR", "09:30"),
+            ("short", "zh_CN", "09时30分"),
+            ("short", "ko_KR", "09시 30분"),
+            ("medium", "fr_FR", "09:30:11"),
+            ("long", "fr_FR", "09:30:11 +00:00"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(
+                        r#"let dt = datetime_rfc3339("{}"); dt.format_time_style("{}", "{}")"#,
+                        timestamp_rfc3339, style, locale
+                    ))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} {} time",
+                style,
+                locale
+            );
+        }
+
+        // test datetime style
+        assert_eq!(
+            engine
+                .eval::<String>(&format!(
+                    r#"let dt = datetime_rfc3339("{}"); dt.format_style("medium", "fr_FR")"#,
+                    timestamp_rfc3339
+                ))
+                .unwrap_or_default(),
+            "9 août 1989 09:30:11",
+            "we should be getting medium french datetime"
+        );
+
+        // test default locale
+        assert_eq!(
+            engine
+                .eval::<String>(&format!(
+                    r#"let dt = datetime_rfc3339("{}"); dt.format_style("short")"#,
+                    timestamp_rfc3339
+                ))
+                .unwrap_or_default(),
+            "08/09/89 09:30",
+            "we should be getting POSIX short datetime"
+        );
+
+        // test invalid style
+        assert!(
+            engine
+                .eval::<String>(&format!(
+                    r#"let dt = datetime_rfc3339("{}"); dt.format_style("tiny")"#,
+                    timestamp_rfc3339
+                ))
+                .is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn localized_parse_works() {
+        let engine = get_engine();
+
+        // test localized names
+        for (timestamp, format, locale) in [
+            ("9 août 1989", "%e %B %Y", "fr_FR"),
+            ("9 AOÛT 1989", "%e %B %Y", "fr_FR"),
+            ("mercredi, 9 août 1989", "%A, %e %B %Y", "fr_FR"),
+            ("Mittwoch, 9. August 1989", "%A, %e. %B %Y", "de_DE"),
+            ("Mi, 9. Aug 1989", "%a, %e. %b %Y", "de_DE"),
+            ("9 августа 1989", "%e %B %Y", "ru_RU"),
+            ("Wednesday, August 9, 1989", "%A, %B %e, %Y", "en_US"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(
+                        r#"let dt = datetime_parse("{} 09:30:11", "{} %H:%M:%S", "{}"); dt.to_string()"#,
+                        timestamp, format, locale
+                    ))
+                    .unwrap_or_default(),
+                "1989-08-09T09:30:11+00:00",
+                "we should be parsing {} using {}",
+                timestamp,
+                locale
+            );
+        }
+
+        // test ambiguous abbreviations are resolved by format position
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_parse("mar 12 mar 2024 00:00", "%a %d %b %Y %H:%M", "es_ES"); dt.to_string()"#)
+                .unwrap_or_default(),
+            "2024-03-12T00:00:00+00:00",
+            "we should be getting Tuesday March 12th"
+        );
+
+        // test round trip
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let dt = datetime_rfc3339("1989-11-22T09:30:11+00:00");
+                let format = "%A %e %B %Y %H:%M:%S";
+
+                let dt2 = datetime_parse(dt.format(format, "pl_PL"), format, "pl_PL");
+
+                dt2.to_string()
+            "#
+                )
+                .unwrap_or_default(),
+            "1989-11-22T09:30:11+00:00",
+            "we should be getting the same DateTime back"
+        );
+
+        // test wrong weekday
+        assert!(
+            engine
+                .eval::<DateTimeFixed>(r#"datetime_parse("jeudi 9 août 1989 00:00", "%A %e %B %Y %H:%M", "fr_FR")"#)
+                .is_err_and(|e| e.to_string().contains("jeudi 9 août 1989 00:00")),
+            "we should be getting an error quoting the original timestamp"
+        );
+
+        // test invalid locale
+        assert!(
+            engine
+                .eval::<DateTimeFixed>(r#"datetime_parse("9 août 1989 00:00", "%e %B %Y %H:%M", "xx_XX")"#)
+                .is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn format_compile_works() {
+        let engine = get_engine();
+
+        // test compiled format
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                let format = format_compile("%Y-%m-%d %H:%M:%S");
+                let out = [];
+
+                for n in 0..3 {
+                    let dt = datetime_unix(618658211);
+                    dt.add_days(n);
+                    out.push(dt.format(format));
+                }
+
+                out[2]
+            "#
+                )
+                .unwrap_or_default(),
+            "1989-08-11 09:30:11",
+            "we should be getting MySQL datetime string"
+        );
+
+        // test compiled localized format
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_unix(618658211); dt.format(format_compile("%A, %B %e", "fr_FR"))"#)
+                .unwrap_or_default(),
+            "mercredi, août  9",
+            "we should be getting pretty french words"
+        );
+
+        // test locale specific specifiers expanded at compile time
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_unix(618658211); dt.format(format_compile("%x", "de_DE"))"#)
+                .unwrap_or_default(),
+            "09.08.1989",
+            "we should be getting german date"
+        );
+
+        // test parse with compiled format
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_parse("1989-08-09 09:30:11", format_compile("%Y-%m-%d %H:%M:%S")); dt.to_string()"#)
+                .unwrap_or_default(),
+            "1989-08-09T09:30:11+00:00",
+            "we should be getting RFC3339 string"
+        );
+
+        // test source
+        assert_eq!(
+            engine
+                .eval::<String>(r#"format_compile("%Y-%m-%d").to_string()"#)
+                .unwrap_or_default(),
+            "%Y-%m-%d",
+            "we should be getting the source format"
+        );
+
+        // test invalid specifiers
+        for format in ["%Y-%Q", "%Y-%", "%.7f"] {
+            assert!(
+                engine.eval::<rhai::Dynamic>(&format!(r#"format_compile("{}")"#, format)).is_err(),
+                "we should be getting an error for {}",
+                format
+            );
+
+            assert!(
+                engine
+                    .eval::<String>(&format!(r#"let dt = datetime_now(); dt.format("{}")"#, format))
+                    .is_err(),
+                "we should be getting an error for {}",
+                format
+            );
+
+            assert!(
+                engine
+                    .eval::<String>(&format!(r#"let dt = datetime_now(); dt.format("{}", "fr_FR")"#, format))
+                    .is_err(),
+                "we should be getting an error for {}",
+                format
+            );
+        }
+
+        // test locale data chrono fails to render
+        assert!(
+            engine
+                .eval::<String>(r#"let dt = datetime_now(); dt.format("%x", "fa_IR")"#)
+                .is_err(),
+            "we should be getting an error instead of a panic"
+        );
+    }
+
+    #[test]
+    fn rfc3339_options_work() {
+        let engine = get_engine();
+
+        let timestamp_rfc3339_nanos = "1989-08-09T09:30:11.123456789+00:00";
+
+        for (precision, use_z, expected) in [
+            ("secs", false, "1989-08-09T09:30:11+00:00"),
+            ("secs", true, "1989-08-09T09:30:11Z"),
+            ("millis", true, "1989-08-09T09:30:11.123Z"),
+            ("micros", false, "1989-08-09T09:30:11.123456+00:00"),
+            ("nanos", true, "1989-08-09T09:30:11.123456789Z"),
+            ("auto", true, "1989-08-09T09:30:11.123456789Z"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(
+                        r#"let dt = datetime_rfc3339("{}"); dt.to_rfc3339("{}", {})"#,
+                        timestamp_rfc3339_nanos, precision, use_z
+                    ))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting RFC3339 string with {} precision",
+                precision
+            );
+        }
+
+        // test precision only
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_unix(618658211); dt.to_rfc3339("millis")"#)
+                .unwrap_or_default(),
+            "1989-08-09T09:30:11.000+00:00",
+            "we should be getting exactly 3 fractional digits"
+        );
+
+        // test "Z" only applies to UTC
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_unix(618658211); dt.timezone("+02:00"); dt.to_rfc3339("secs", true)"#)
+                .unwrap_or_default(),
+            "1989-08-09T11:30:11+02:00",
+            "we should be getting offset"
+        );
+
+        // test iso8601 basic
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("2024-01-01T12:00:00.5+00:00"); dt.to_iso8601_basic()"#)
+                .unwrap_or_default(),
+            "20240101T120000Z",
+            "we should be getting basic format in UTC"
+        );
+
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("2024-01-01T12:00:00-05:30"); dt.to_iso8601_basic()"#)
+                .unwrap_or_default(),
+            "20240101T120000-0530",
+            "we should be getting basic format with offset"
+        );
+
+        // test invalid precision
+        assert!(
+            engine.eval::<String>(r#"let dt = datetime_now(); dt.to_rfc3339("decis")"#).is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn http_date_works() {
+        let engine = get_engine();
+
+        // test all three RFC9110 forms
+        for timestamp in [
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(r#"let dt = datetime_http("{}"); dt.to_string()"#, timestamp))
+                    .unwrap_or_default(),
+                "1994-11-06T08:49:37+00:00",
+                "we should be parsing {}",
+                timestamp
+            );
+        }
+
+        // test output normalized to GMT
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("1994-11-06T02:49:37-06:00"); dt.to_http_date()"#)
+                .unwrap_or_default(),
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "we should be getting IMF-fixdate"
+        );
+
+        // test cookie expires
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_unix(618658211); dt.to_cookie_expires()"#)
+                .unwrap_or_default(),
+            "Wed, 09 Aug 1989 09:30:11 GMT",
+            "we should be getting cookie date"
+        );
+
+        // test round trip
+        assert_eq!(
+            engine
+                .eval::<rhai::INT>(r#"let dt = datetime_unix(618658211); datetime_http(dt.to_http_date()).timestamp()"#)
+                .unwrap_or_default(),
+            618658211 as rhai::INT,
+            "we should be getting the same timestamp"
+        );
+
+        // test garbage
+        assert!(
+            engine
+                .eval::<DateTimeFixed>(r#"datetime_http("Sun, 06 Nov 1994 08:49:37 +0100")"#)
+                .is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn log_formats_work() {
+        let engine = get_engine();
+
+        // test rfc3164 with reference year
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3164("Oct  1 22:14:15", 2003); dt.to_string()"#)
+                .unwrap_or_default(),
+            "2003-10-01T22:14:15+00:00",
+            "we should be getting RFC3339 string"
+        );
+
+        // test rfc3164 year inference never lands far in the future
+        assert!(
+            engine
+                .eval::<bool>(r#"let dt = datetime_syslog("Jan  1 00:00:00"); dt.diff(datetime_now()).days < 2"#)
+                .unwrap_or_default(),
+            "we should be getting a date in the past"
+        );
+
+        // test rfc3164 year inference going back to a year where the date exists
+        assert!(
+            engine
+                .eval::<bool>(r#"let dt = datetime_syslog("Feb 29 12:00:00"); let limit = datetime_now(); limit.add_days(1); dt.is_leap_year && dt <= limit"#)
+                .unwrap_or_default(),
+            "we should be getting Feb 29 of the last leap year"
+        );
+
+        assert!(
+            engine
+                .eval::<bool>(r#"let dt = datetime_syslog("Dec 31 23:59:59"); let limit = datetime_now(); limit.add_days(1); dt <= limit"#)
+                .unwrap_or_default(),
+            "we should be getting the last Dec 31 that is not in the future"
+        );
+
+        // test rfc3164 output
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("2003-10-01T22:14:15+00:00"); dt.to_rfc3164()"#)
+                .unwrap_or_default(),
+            "Oct  1 22:14:15",
+            "we should be getting RFC3164 string"
+        );
+
+        // test rfc5424
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc5424("2003-10-11T22:14:15.003Z"); dt.to_rfc5424()"#)
+                .unwrap_or_default(),
+            "2003-10-11T22:14:15.003000Z",
+            "we should be getting RFC5424 string"
+        );
+
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc5424("2003-08-24T05:14:15-07:00"); dt.to_rfc5424()"#)
+                .unwrap_or_default(),
+            "2003-08-24T05:14:15-07:00",
+            "we should be getting RFC5424 string"
+        );
+
+        assert!(
+            engine.eval::<DateTimeFixed>(r#"datetime_rfc5424("-")"#).is_err(),
+            "we should be getting an error for NILVALUE"
+        );
+
+        // test clf
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_clf("[10/Oct/2000:13:55:36 -0700]"); `${dt} ${dt.to_clf()}`"#)
+                .unwrap_or_default(),
+            "2000-10-10T13:55:36-07:00 [10/Oct/2000:13:55:36 -0700]",
+            "we should be getting CLF string"
+        );
+
+        // test log4j
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_log4j("2024-01-01 12:00:00,123"); `${dt.timestamp_millis()} ${dt.to_log4j()}`"#)
+                .unwrap_or_default(),
+            "1704110400123 2024-01-01 12:00:00,123",
+            "we should be getting Log4j string"
+        );
+
+        assert!(
+            engine
+                .eval::<DateTimeFixed>(r#"datetime_log4j("2024-01-01T12:00:00.123")"#)
+                .is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn sql_works() {
+        let engine = get_engine();
+
+        let timestamp_rfc3339 = "1989-08-09T11:30:11.123456789+02:00";
+
+        // test to_sql
+        for (dialect, expected) in [
+            ("mysql", "1989-08-09 09:30:11.123456"),
+            ("postgres", "1989-08-09 11:30:11.123456+02"),
+            ("sqlite", "1989-08-09 09:30:11.123"),
+            ("mssql", "1989-08-09 11:30:11.1234567 +02:00"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(
+                        r#"let dt = datetime_rfc3339("{}"); dt.to_sql("{}")"#,
+                        timestamp_rfc3339, dialect
+                    ))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} string",
+                dialect
+            );
+        }
+
+        // test whole seconds
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"let dt = datetime_rfc3339("1989-08-09T11:30:11+05:30"); `${dt.to_sql("mysql")}|${dt.to_sql("postgres")}`"#
+                )
+                .unwrap_or_default(),
+            "1989-08-09 06:00:11|1989-08-09 11:30:11+05:30",
+            "we should be getting no fractional digits"
+        );
+
+        // test sub-microsecond fraction
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_nanos(618658211000000999); dt.to_sql("mysql")"#)
+                .unwrap(),
+            "1989-08-09 09:30:11",
+            "we should be getting no fractional digits below a microsecond"
+        );
+
+        // test sqlite numeric forms
+        assert_eq!(
+            engine
+                .eval::<rhai::INT>(r#"let dt = datetime_unix(618658211); dt.to_sql("sqlite_unix")"#)
+                .unwrap_or_default(),
+            618658211 as rhai::INT,
+            "we should be getting UNIX timestamp"
+        );
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<rhai::FLOAT>(r#"let dt = datetime_rfc3339("2000-01-01T12:00:00+00:00"); dt.to_sql("sqlite_julian")"#)
+                .unwrap_or_default(),
+            2451545.0,
+            "we should be getting J2000 julian day"
+        );
+
+        // test datetime_sql
+        for (value, dialect, expected) in [
+            ("1989-08-09 09:30:11", "mysql", "1989-08-09T09:30:11+00:00"),
+            ("1989-08-09 09:30:11.123456", "mysql", "1989-08-09T09:30:11.123456+00:00"),
+            ("1989-08-09 11:30:11.5+02", "postgres", "1989-08-09T11:30:11.500+02:00"),
+            ("1989-08-09 11:30:11+05:30", "postgresql", "1989-08-09T11:30:11+05:30"),
+            ("1989-08-09 09:30:11.123", "sqlite", "1989-08-09T09:30:11.123+00:00"),
+            ("1989-08-09T09:30:11", "sqlite", "1989-08-09T09:30:11+00:00"),
+            ("1989-08-09 09:30", "sqlite", "1989-08-09T09:30:00+00:00"),
+            ("1989-08-09T09:30:11.123Z", "sqlite", "1989-08-09T09:30:11.123+00:00"),
+            ("1989-08-09T11:30:11+02:00", "sqlite", "1989-08-09T11:30:11+02:00"),
+            ("1989-08-09 11:30-02:00", "sqlite", "1989-08-09T11:30:00-02:00"),
+            #[cfg(not(feature = "no_float"))]
+            ("2451545.0", "sqlite_julian", "2000-01-01T12:00:00+00:00"),
+            ("618658211", "sqlite_unix", "1989-08-09T09:30:11+00:00"),
+            ("1989-08-09 11:30:11.1234567 +02:00", "mssql", "1989-08-09T11:30:11.123456700+02:00"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(r#"let dt = datetime_sql("{}", "{}"); dt.to_string()"#, value, dialect))
+                    .unwrap_or_default(),
+                expected,
+                "we should be parsing {} string",
+                dialect
+            );
+        }
+
+        // test numeric datetime_sql
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_sql(0, "sqlite_unix"); dt.to_string()"#)
+                .unwrap_or_default(),
+            "1970-01-01T00:00:00+00:00",
+            "we should be parsing integers"
+        );
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_sql(2451545.0, "sqlite_julian"); dt.to_string()"#)
+                .unwrap_or_default(),
+            "2000-01-01T12:00:00+00:00",
+            "we should be parsing floats"
+        );
+
+        // test invalid dialect
+        assert!(
+            engine.eval::<String>(r#"let dt = datetime_now(); dt.to_sql("oracle")"#).is_err(),
+            "we should be getting an error"
+        );
+
+        assert!(
+            engine.eval::<DateTimeFixed>(r#"datetime_sql("1989-08-09", "mysql")"#).is_err(),
+            "we should be getting an error"
+        );
+
+        #[cfg(feature = "no_float")]
+        assert!(
+            engine
+                .eval::<DateTimeFixed>(r#"datetime_sql("2451545.0", "sqlite_julian")"#)
+                .is_err(),
+            "we should be getting an error without float support"
+        );
+    }
+
+    #[test]
+    fn epochs_work() {
+        let engine = get_engine();
+
+        // test Excel serials including the fictitious 1900-02-29
+        for (serial, expected) in [
+            ("0", "1899-12-31T00:00:00+00:00"),
+            ("1", "1900-01-01T00:00:00+00:00"),
+            ("59", "1900-02-28T00:00:00+00:00"),
+            ("61", "1900-03-01T00:00:00+00:00"),
+            #[cfg(not(feature = "no_float"))]
+            ("32729.5", "1989-08-09T12:00:00+00:00"),
+            ("31267, 1904", "1989-08-09T00:00:00+00:00"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(r#"let dt = datetime_excel({}); dt.to_string()"#, serial))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} from Excel serial {}",
+                expected,
+                serial
+            );
+        }
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"let dt = datetime_rfc3339("1989-08-09T12:00:00+00:00"); `${dt.to_excel()} ${dt.to_excel(1904)} ${datetime_excel(59).to_excel()}`"#
+                )
+                .unwrap_or_default(),
+            "32729.5 31267.5 59.0",
+            "we should be getting Excel serials"
+        );
+
+        // test tick based epochs
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_unix(0); `${dt.to_filetime()} ${dt.to_dotnet_ticks()} ${dt.to_ntp()}`"#)
+                .unwrap_or_default(),
+            "116444736000000000 621355968000000000 2208988800",
+            "we should be getting epoch offsets"
+        );
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine.eval::<rhai::FLOAT>("datetime_unix(978307200).to_cocoa()").unwrap_or(-1.0),
+            0.0,
+            "we should be getting Cocoa epoch offset"
+        );
+
+        for (constructor, expected) in [
+            ("datetime_filetime(116444736000000001)", "1970-01-01T00:00:00.000000100+00:00"),
+            ("datetime_dotnet_ticks(0)", "0001-01-01T00:00:00+00:00"),
+            ("datetime_ntp(2208988800, 2147483648)", "1970-01-01T00:00:00.500+00:00"),
+            ("datetime_cocoa(-1)", "2000-12-31T23:59:59+00:00"),
+            #[cfg(not(feature = "no_float"))]
+            ("datetime_cocoa(-0.25)", "2000-12-31T23:59:59.750+00:00"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(r#"let dt = {}; dt.to_string()"#, constructor))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                constructor
+            );
+        }
+
+        assert_eq!(
+            engine
+                .eval::<rhai::INT>(r#"let dt = datetime_ntp(0, 2147483648); dt.to_ntp_fraction()"#)
+                .unwrap_or_default(),
+            2147483648,
+            "we should be getting NTP fraction"
+        );
+
+        // test out of range values
+        for script in [
+            "datetime_excel(60)",
+            #[cfg(not(feature = "no_float"))]
+            "datetime_excel(60.5)",
+            "datetime_excel(-1)",
+            "datetime_excel(2958466)",
+            "datetime_excel(1, 1901)",
+            "datetime_filetime(-1)",
+            "datetime_dotnet_ticks(3155378976000000000)",
+            "datetime_ntp(0, 4294967296)",
+            r#"datetime_rfc3339("1600-12-31T00:00:00+00:00").to_filetime()"#,
+            #[cfg(not(feature = "no_float"))]
+            r#"datetime_rfc3339("1899-12-30T00:00:00+00:00").to_excel()"#,
+        ] {
+            assert!(
+                engine.eval::<rhai::Dynamic>(script).is_err(),
+                "we should be getting an error for {}",
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn julian_day_works() {
+        let engine = get_engine();
+
+        for (constructor, expected) in [
+            #[cfg(not(feature = "no_float"))]
+            ("datetime_julian_day(2451545.0)", "2000-01-01T12:00:00+00:00"),
+            ("datetime_julian_day(2451545)", "2000-01-01T12:00:00+00:00"),
+            ("datetime_julian_day(0)", "-4713-11-24T12:00:00+00:00"),
+            ("datetime_mjd(0)", "1858-11-17T00:00:00+00:00"),
+            ("datetime_mjd(51544)", "2000-01-01T00:00:00+00:00"),
+            #[cfg(not(feature = "no_float"))]
+            ("datetime_mjd(51544.5)", "2000-01-01T12:00:00+00:00"),
+            #[cfg(not(feature = "no_float"))]
+            ("datetime_mjd(47747.0 + 34211.123 / 86400.0)", "1989-08-09T09:30:11.123+00:00"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(r#"let dt = {}; dt.to_string()"#, constructor))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                constructor
+            );
+        }
+
+        // test output keeps sub-second precision
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<rhai::FLOAT>(r#"let dt = datetime_rfc3339("2000-01-01T00:00:00.5+00:00"); dt.mjd()"#)
+                .unwrap_or_default(),
+            51544.0 + 0.5 / 86400.0,
+            "we should be getting fractional MJD"
+        );
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<rhai::FLOAT>(r#"let dt = datetime_rfc3339("2000-01-01T14:00:00+02:00"); dt.julian_day()"#)
+                .unwrap_or_default(),
+            2451545.0,
+            "we should be getting J2000 regardless of offset"
+        );
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_rfc3339("1989-08-09T09:30:11.123+00:00"); datetime_mjd(dt.mjd()).to_string()"#)
+                .unwrap_or_default(),
+            "1989-08-09T09:30:11.123+00:00",
+            "we should be round-tripping MJD"
+        );
+
+        for script in [
+            #[cfg(not(feature = "no_float"))]
+            "datetime_julian_day(1e300)",
+            "datetime_julian_day(9223372036854775807)",
+            "datetime_mjd(-9223372036854775807)",
+        ] {
+            assert!(
+                engine.eval::<rhai::Dynamic>(script).is_err(),
+                "we should be getting an error for {}",
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn identifiers_work() {
+        let engine = get_engine();
+
+        for (constructor, expected) in [
+            (
+                r#"datetime_from_ulid("01ARZ3NDEKTSV4RRFFQ69G5FAV")"#,
+                "2016-07-30T23:54:10.259+00:00",
+            ),
+            (
+                r#"datetime_from_ulid("01arz3ndektsv4rrffq69g5fav")"#,
+                "2016-07-30T23:54:10.259+00:00",
+            ),
+            (
+                r#"datetime_from_uuid("C232AB00-9414-11EC-B3C8-9F6BDECED846")"#,
+                "2022-02-22T19:22:22+00:00",
+            ),
+            (
+                r#"datetime_from_uuid("1EC9414C-232A-6B00-B3C8-9F6BDECED846")"#,
+                "2022-02-22T19:22:22+00:00",
+            ),
+            (
+                r#"datetime_from_uuid("017F22E2-79B0-7CC3-98C4-DC0C0C07398F")"#,
+                "2022-02-22T19:22:22+00:00",
+            ),
+            (
+                r#"datetime_from_uuid("{017f22e279b07cc398c4dc0c0c07398f}")"#,
+                "2022-02-22T19:22:22+00:00",
+            ),
+            ("datetime_from_snowflake(1212161209625182208)", "2019-12-31T23:58:47.897+00:00"),
+            (
+                "datetime_from_snowflake(175928847299117063, 1420070400000)",
+                "2016-04-30T11:18:25.796+00:00",
+            ),
+            (
+                r#"datetime_from_snowflake("175928847299117063", 1420070400000)"#,
+                "2016-04-30T11:18:25.796+00:00",
+            ),
+            (
+                r#"datetime_from_object_id("507f1f77bcf86cd799439011")"#,
+                "2012-10-17T21:13:27+00:00",
+            ),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(r#"let dt = {}; dt.to_string()"#, constructor))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                constructor
+            );
+        }
+
+        // test invalid identifiers
+        for script in [
+            r#"datetime_from_ulid("81ARZ3NDEKTSV4RRFFQ69G5FAV")"#,
+            r#"datetime_from_ulid("01ARZ3NDEKTSV4RRFFQ69G5FA")"#,
+            r#"datetime_from_uuid("f47ac10b-58cc-4372-a567-0e02b2c3d479")"#,
+            r#"datetime_from_uuid("not-a-uuid")"#,
+            "datetime_from_snowflake(-1)",
+            r#"datetime_from_object_id("507f1f77bcf86cd79943901g")"#,
+        ] {
+            assert!(
+                engine.eval::<rhai::Dynamic>(script).is_err(),
+                "we should be getting an error for {}",
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn epoch_auto_works() {
+        let engine = get_engine();
+
+        for (value, expected) in [
+            ("618658211", "1989-08-09T09:30:11+00:00"),
+            ("618658211123", "1989-08-09T09:30:11.123+00:00"),
+            ("618658211123456", "1989-08-09T09:30:11.123456+00:00"),
+            ("618658211123456789", "1989-08-09T09:30:11.123456789+00:00"),
+            ("-1", "1969-12-31T23:59:59+00:00"),
+            ("-618658211123", "1950-05-25T14:29:48.877+00:00"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(r#"let dt = datetime_epoch({}); dt.to_string()"#, value))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                value
+            );
+        }
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let dt = datetime_epoch(618658211.25); dt.to_string()"#)
+                .unwrap_or_default(),
+            "1989-08-09T09:30:11.250+00:00",
+            "we should be reading floats as seconds"
+        );
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<rhai::FLOAT>(r#"let dt = datetime_rfc3339("1989-08-09T09:30:11.5+00:00"); dt.timestamp_f64()"#)
+                .unwrap_or_default(),
+            618658211.5,
+            "we should be getting fractional timestamp"
+        );
+    }
+
+    #[test]
+    fn relative_works() {
+        let engine = get_engine();
+
+        // 1989-08-09 is a Wednesday
+        for (expr, expected) in [
+            ("tomorrow 9am", "1989-08-10T09:00:00+00:00"),
+            ("next friday", "1989-08-11T00:00:00+00:00"),
+            ("+3 days", "1989-08-12T09:30:11+00:00"),
+            ("last day of next month", "1989-09-30T00:00:00+00:00"),
+            ("last day", "1989-08-08T00:00:00+00:00"),
+            ("next day at 9am", "1989-08-10T09:00:00+00:00"),
+            ("in 2 weeks", "1989-08-23T09:30:11+00:00"),
+            ("3 hours ago", "1989-08-09T06:30:11+00:00"),
+            ("a week ago", "1989-08-02T09:30:11+00:00"),
+            ("wednesday", "1989-08-09T00:00:00+00:00"),
+            ("next wednesday", "1989-08-16T00:00:00+00:00"),
+            ("last wednesday", "1989-08-02T00:00:00+00:00"),
+            ("noon tomorrow", "1989-08-10T12:00:00+00:00"),
+            ("yesterday midnight", "1989-08-08T00:00:00+00:00"),
+            ("first day of this year", "1989-01-01T00:00:00+00:00"),
+            ("next month at 5:30 pm", "1989-09-09T17:30:00+00:00"),
+            ("Friday, 21:15", "1989-08-11T21:15:00+00:00"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(
+                        r#"let base = datetime_rfc3339("1989-08-09T09:30:11+00:00"); datetime_relative("{}", base).to_string()"#,
+                        expr
+                    ))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                expr
+            );
+        }
+
+        // test base offset is kept
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"let base = datetime_rfc3339("1989-08-09T23:30:11+02:00"); datetime_relative("tomorrow 9am", base).to_string()"#
+                )
+                .unwrap_or_default(),
+            "1989-08-10T09:00:00+02:00",
+            "we should be getting tomorrow in the base offset"
+        );
+
+        // test word table
+        for (expr, expected) in [
+            ("vendredi prochain", "1989-08-11T00:00:00+00:00"),
+            ("dans 3 jours", "1989-08-12T09:30:11+00:00"),
+            ("il y a 2 jours", "1989-08-07T09:30:11+00:00"),
+            ("Demain 9h", "1989-08-10T09:00:00+00:00"),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<String>(&format!(
+                        r#"
+                            let base = datetime_rfc3339("1989-08-09T09:30:11+00:00");
+                            let words = #{{ "demain": "tomorrow", "vendredi": "friday", "prochain": "next", "dans": "in", "jours": "days", "il y a": "-", "9h": "9:00" }};
+                            datetime_relative("{}", base, words).to_string()
+                        "#,
+                        expr
+                    ))
+                    .unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                expr
+            );
+        }
+
+        // test errors point at the unparsed part
+        for (expr, expected) in [
+            ("tomorrow 9xm", r#"expected a relative date at position 9, found "9xm""#),
+            ("in 3", "expected a unit at position 4, found end of input"),
+            ("in 3 parsecs", r#"expected a unit at position 5, found "parsecs""#),
+            (
+                "first day of next hour",
+                r#"expected week, month or year at position 13, found "next hour""#,
+            ),
+            ("", "expected a relative date at position 0, found end of input"),
+        ] {
+            let error = engine
+                .eval::<String>(&format!(r#"datetime_relative("{}", datetime_unix(0)).to_string()"#, expr))
+                .map_err(|e| e.to_string())
+                .unwrap_err();
+
+            assert!(
+                error.contains(expected),
+                "we should be getting an error pointing at {} but got {}",
+                expected,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn timedelta_human_works() {
+        let engine = get_engine();
+
+        for (duration, expected) in [
+            ("90s", 90_000),
+            ("1h30m", 5_400_000),
+            ("1.5h", 5_400_000),
+            ("-300ms", -300),
+            ("2 days 3 hours", 183_600_000),
+            ("1 hour, 30 minutes and 15 seconds", 5_415_000),
+            ("1w", 604_800_000),
+            ("0.25 sec", 250),
+            ("0", 0),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<rhai::INT>(&format!(r#"let td = timedelta_parse_human("{}"); td.milliseconds"#, duration))
+                    .unwrap_or(-1),
+                expected,
+                "we should be getting {} milliseconds from {}",
+                expected,
+                duration
+            );
+        }
+
+        assert_eq!(
+            engine
+                .eval::<rhai::INT>(r#"let td = timedelta_parse_human("1.000000001s 2us"); td.nanoseconds"#)
+                .unwrap_or_default(),
+            1_000_002_001,
+            "we should be keeping nanosecond precision"
+        );
+
+        // test errors point at the unparsed part
+        for (duration, expected) in [
+            ("1h30", "expected a unit at position 4, found end of input"),
+            ("5 parsecs", r#"expected a unit at position 2, found "parsecs""#),
+            ("h", r#"expected a number at position 0, found "h""#),
+            ("", "expected a duration at position 0, found end of input"),
+        ] {
+            let error = engine
+                .eval::<Timedelta>(&format!(r#"timedelta_parse_human("{}")"#, duration))
+                .map_err(|e| e.to_string())
+                .unwrap_err();
+
+            assert!(
+                error.contains(expected),
+                "we should be getting an error pointing at {} but got {}",
+                expected,
+                error
+            );
+        }
+
+        // test formatting
+        for (script, expected) in [
+            ("timedelta_minutes(90).format_compact()", "1h 30m"),
+            ("timedelta_millis(-90500).format_compact()", "-1m 30s 500ms"),
+            ("timedelta_zero().format_compact()", "0s"),
+            ("timedelta_minutes(90).format_verbose()", "1 hour, 30 minutes"),
+            ("timedelta_seconds(90061).format_verbose()", "1 day, 1 hour, 1 minute, 1 second"),
+            ("timedelta_seconds(90061).format_verbose(2)", "1 day, 1 hour"),
+            ("timedelta_zero().format_verbose()", "0 seconds"),
+            (
+                r#"timedelta_minutes(150).format_verbose(3, #{ "hours": "Stunden", "minutes": "Minuten", "last_separator": " und " })"#,
+                "2 Stunden und 30 Minuten",
+            ),
+        ] {
+            assert_eq!(
+                engine.eval::<String>(script).unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        // test round trip
+        assert!(
+            engine
+                .eval::<bool>(r#"let td = timedelta_seconds(93784); timedelta_parse_human(td.format_verbose()).seconds == td.seconds"#)
+                .unwrap_or_default(),
+            "we should be parsing verbose output back"
+        );
+
+        assert!(
+            engine.eval::<String>("timedelta_minutes(1).format_verbose(0)").is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn timedelta_clock_works() {
+        let engine = get_engine();
+
+        for (clock, expected) in [
+            ("01:23:45.678", 5_025_678),
+            ("-00:05:00", -300_000),
+            ("3:45", 225_000),
+            ("123:00:00", 442_800_000),
+            ("00:00:01.5", 1_500),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<rhai::INT>(&format!(r#"let td = timedelta_clock("{}"); td.milliseconds"#, clock))
+                    .unwrap_or(-1),
+                expected,
+                "we should be getting {} milliseconds from {}",
+                expected,
+                clock
+            );
+        }
+
+        for clock in ["01:60:00", "1:2:3", "12", "01:00:00.1234567890", "aa:bb", "-"] {
+            assert!(
+                engine.eval::<Timedelta>(&format!(r#"timedelta_clock("{}")"#, clock)).is_err(),
+                "we should be getting an error for {}",
+                clock
+            );
+        }
+
+        for clock in [
+            "100000000000000000000000000000:00:00",
+            "99999999999999999999999999999999999999:00",
+            "1000000000000000000000000000000000000000:00:00",
+        ] {
+            assert!(
+                engine
+                    .eval::<Timedelta>(&format!(r#"timedelta_clock("{}")"#, clock))
+                    .is_err_and(|e| e.to_string().contains("Delta out of range")),
+                "we should be getting an out-of-range error for {}",
+                clock
+            );
+        }
+
+        for (script, expected) in [
+            (r#"timedelta_clock("01:23:45.678").format("%H:%M:%S%.3f")"#, "01:23:45.678"),
+            (r#"timedelta_clock("-00:05:00").format("%-%H:%M:%S")"#, "-00:05:00"),
+            (r#"timedelta_minutes(5).format("%+%H:%M")"#, "+00:05"),
+            (r#"timedelta_hours(123).format("%H:%M")"#, "123:00"),
+            (r#"timedelta_hours(123).format("%Dd %hh")"#, "05d 03h"),
+            (r#"timedelta_seconds(225).format("%m:%S")"#, "3:45"),
+            (r#"timedelta_seconds(225).format("%ss")"#, "225s"),
+            (r#"timedelta_millis(1500).format("%S%.f")"#, "01.5"),
+            (r#"timedelta_seconds(1).format("%S%.f")"#, "01"),
+            (r#"timedelta_micros(1234567).format("%S.%6f %9f")"#, "01.234567 234567000"),
+            (r#"timedelta_seconds(1).format("100%%")"#, "100%"),
+        ] {
+            assert_eq!(
+                engine.eval::<String>(script).unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        assert!(
+            engine.eval::<String>(r#"timedelta_seconds(1).format("%Q")"#).is_err(),
+            "we should be getting an error"
+        );
+    }
+
+    #[test]
+    fn timedelta_arithmetic_works() {
+        let engine = get_engine();
+
+        for (script, expected) in [
+            ("(timedelta_minutes(10) * 3).seconds", 1_800),
+            ("(3 * timedelta_minutes(10)).seconds", 1_800),
+            ("(timedelta_seconds(7) / 2).milliseconds", 3_500),
+            ("(timedelta_minutes(-7) / 2).seconds", -210),
+            ("(timedelta_minutes(100) % timedelta_minutes(30)).seconds", 600),
+            ("(timedelta_minutes(-100) % timedelta_minutes(30)).seconds", -600),
+            ("(timedelta_minutes(10) + timedelta_seconds(5)).seconds", 605),
+            ("(timedelta_minutes(10) - timedelta_seconds(5)).seconds", 595),
+            ("let td = timedelta_seconds(5); td *= 4; td.seconds", 20),
+            ("timedelta_max().saturating_mul(2).milliseconds", TimeDelta::MAX.num_milliseconds()),
+            ("timedelta_max().saturating_mul(-2).milliseconds", TimeDelta::MIN.num_milliseconds()),
+            (
+                "timedelta_min().saturating_sub(timedelta_seconds(1)).milliseconds",
+                TimeDelta::MIN.num_milliseconds(),
+            ),
+            (
+                "timedelta_max().saturating_add(timedelta_seconds(1)).milliseconds",
+                TimeDelta::MAX.num_milliseconds(),
+            ),
+            ("timedelta_seconds(5).checked_mul(3).seconds", 15),
+        ] {
+            assert_eq!(
+                engine.eval::<rhai::INT>(script).unwrap_or(-1),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        #[cfg(not(feature = "no_float"))]
+        for (script, expected) in [
+            ("(timedelta_hours(1) * 1.5).minutes", 90),
+            ("(1.5 * timedelta_hours(1)).minutes", 90),
+            ("(timedelta_minutes(10) / 2.5).seconds", 240),
+            ("timedelta_seconds(5).checked_div(0.5).seconds", 10),
+            ("(timedelta_nanoseconds(147) / 98.0).nanoseconds", 2),
+            ("(timedelta_seconds(1) / 0.000000001).seconds", 1_000_000_000),
+            (
+                "timedelta_max().saturating_mul(1.5).milliseconds",
+                TimeDelta::MAX.num_milliseconds(),
+            ),
+            (
+                "timedelta_seconds(-1).saturating_mul(1e300).milliseconds",
+                TimeDelta::MIN.num_milliseconds(),
+            ),
+        ] {
+            assert_eq!(
+                engine.eval::<rhai::INT>(script).unwrap_or(-1),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<rhai::FLOAT>("timedelta_hours(1) / timedelta_minutes(40)")
+                .unwrap_or_default(),
+            1.5,
+            "we should be getting ratio"
+        );
+
+        // test checked variants
+        for script in [
+            "timedelta_max().checked_mul(2)",
+            "timedelta_seconds(1).checked_div(0)",
+            "timedelta_seconds(1).checked_rem(timedelta_zero())",
+            "timedelta_max().checked_add(timedelta_seconds(1))",
+            "timedelta_min().checked_sub(timedelta_seconds(1))",
+        ] {
+            assert!(engine.eval::<()>(script).is_ok(), "we should be getting unit from {}", script);
+        }
+
+        // test errors
+        for script in [
+            "timedelta_max() * 2",
+            "timedelta_seconds(1) / 0",
+            "timedelta_seconds(1) % timedelta_zero()",
+            "timedelta_max() + timedelta_seconds(1)",
+            #[cfg(not(feature = "no_float"))]
+            "timedelta_seconds(1).saturating_mul(0.0 / 0.0)",
+            #[cfg(not(feature = "no_float"))]
+            "timedelta_zero().saturating_mul(1e300 * 1e300)",
+        ] {
+            assert!(
+                engine.eval::<Timedelta>(script).is_err(),
+                "we should be getting an error for {}",
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn timedelta_totals_work() {
+        let engine = get_engine();
+
+        #[cfg(not(feature = "no_float"))]
+        for (script, expected) in [
+            ("timedelta_seconds(90).total_minutes_f", 1.5),
+            ("timedelta_millis(1500).total_seconds_f()", 1.5),
+            ("timedelta_minutes(-90).total_hours_f", -1.5),
+            ("timedelta_hours(36).total_days_f", 1.5),
+        ] {
+            assert_eq!(
+                engine.eval::<rhai::FLOAT>(script).unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                        let td = timedelta_days(-1);
+                        td.sub(timedelta_seconds(3723, 4005006));
+                        let c = td.components();
+                        `${c.sign} ${c.days} ${c.hours} ${c.minutes} ${c.seconds} ${c.millis} ${c.nanos}`
+                    "#
+                )
+                .unwrap_or_default(),
+            "-1 1 1 2 3 4 5006",
+            "we should be getting normalized components"
+        );
+
+        assert_eq!(
+            engine.eval::<rhai::INT>("timedelta_zero().components.sign").unwrap_or(-2),
+            0,
+            "we should be getting zero sign"
+        );
+    }
+
+    #[test]
+    fn timedelta_sign_works() {
+        let engine = get_engine();
+
+        for (script, expected) in [
+            ("timedelta_seconds(-5).is_negative", true),
+            ("timedelta_seconds(5).is_negative()", false),
+            ("timedelta_zero().is_positive", false),
+            ("timedelta_seconds(5).is_positive()", true),
+        ] {
+            assert_eq!(
+                engine.eval::<bool>(script).unwrap_or(!expected),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        for (script, expected) in [
+            ("timedelta_seconds(-5).signum", -1),
+            ("timedelta_zero().signum()", 0),
+            ("timedelta_seconds(5).signum", 1),
+            ("(-timedelta_seconds(5)).seconds", -5),
+            ("timedelta_seconds(-5).neg().seconds", 5),
+            ("timedelta_seconds(3).abs_diff(timedelta_seconds(10)).seconds", 7),
+            ("timedelta_seconds(10).abs_diff(timedelta_seconds(3)).seconds", 7),
+            ("let td = timedelta_seconds(-5); let a = td.abs(); td.seconds * 10 + a.seconds", -45),
+        ] {
+            assert_eq!(
+                engine.eval::<rhai::INT>(script).unwrap(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        assert!(
+            engine.eval::<Timedelta>("timedelta_max().abs_diff(timedelta_min())").is_err(),
+            "we should be getting an overflow error"
+        );
+    }
+
+    #[test]
+    fn timedelta_rounding_works() {
+        let engine = get_engine();
+
+        for (script, expected) in [
+            ("timedelta_seconds(61).ceil(\"minute\").format_compact()", "2m"),
+            ("timedelta_seconds(60).ceil(timedelta_minutes(1)).format_compact()", "1m"),
+            ("timedelta_seconds(-61).ceil(\"m\").format_compact()", "-1m"),
+            ("timedelta_seconds(119).floor(\"minute\").format_compact()", "1m"),
+            ("timedelta_seconds(-61).floor(\"minute\").format_compact()", "-2m"),
+            ("timedelta_minutes(52).round(timedelta_minutes(15)).format_compact()", "45m"),
+            ("timedelta_minutes(53).round(timedelta_minutes(15)).format_compact()", "1h"),
+            ("timedelta_seconds(90).round(\"minute\").format_compact()", "2m"),
+            ("timedelta_seconds(-90).round(\"minute\").format_compact()", "-2m"),
+            ("timedelta_seconds(90).round(\"minute\", \"half_even\").format_compact()", "2m"),
+            ("timedelta_seconds(150).round(\"minute\", \"half_even\").format_compact()", "2m"),
+            (
+                "timedelta_seconds(-150).round(timedelta_minutes(1), \"half_even\").format_compact()",
+                "-2m",
+            ),
+            ("timedelta_nanos(1500).round(\"us\").format_compact()", "2µs"),
+            ("timedelta_nanos(1499).round(\"us\").format_compact()", "1µs"),
+        ] {
+            assert_eq!(
+                engine.eval::<String>(script).unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        for script in [
+            "timedelta_seconds(5).round(timedelta_zero())",
+            "timedelta_seconds(5).floor(\"fortnight\")",
+            "timedelta_seconds(5).round(\"s\", \"bankers\")",
+            "timedelta_max().ceil(\"day\")",
+        ] {
+            assert!(
+                engine.eval::<Timedelta>(script).is_err(),
+                "we should be getting an error from {}",
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn comparison_works() {
+        let engine = get_engine();
+
+        for (script, expected) in [
+            ("datetime_unix(0).compare(datetime_unix(1))", -1),
+            ("compare(datetime_unix(1), datetime_unix(0))", 1),
+            (
+                "datetime_rfc3339(\"2000-01-01T02:00:00+02:00\").cmp(datetime_rfc3339(\"2000-01-01T00:00:00Z\"))",
+                0,
+            ),
+            ("timedelta_seconds(5).compare(timedelta_seconds(6))", -1),
+            ("compare(timedelta_seconds(5), timedelta_seconds(5))", 0),
+            ("min(datetime_unix(10), datetime_unix(5)).timestamp()", 5),
+            ("max(datetime_unix(10), datetime_unix(5)).timestamp()", 10),
+            ("min(timedelta_seconds(10), timedelta_seconds(-5)).seconds", -5),
+            ("max(timedelta_seconds(10), timedelta_seconds(-5)).seconds", 10),
+            ("min([datetime_unix(7), datetime_unix(3), datetime_unix(9)]).timestamp()", 3),
+            ("max([datetime_unix(7), datetime_unix(3), datetime_unix(9)]).timestamp()", 9),
+            ("min([timedelta_seconds(7), timedelta_seconds(-3)]).seconds", -3),
+            ("max([timedelta_seconds(7), timedelta_seconds(-3)]).seconds", 7),
+            ("datetime_unix(50).clamp(datetime_unix(0), datetime_unix(10)).timestamp()", 10),
+            ("datetime_unix(5).clamp(datetime_unix(0), datetime_unix(10)).timestamp()", 5),
+            ("timedelta_seconds(-5).clamp(timedelta_zero(), timedelta_seconds(10)).seconds", 0),
+        ] {
+            assert_eq!(
+                engine.eval::<rhai::INT>(script).unwrap_or(-2),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        for (script, expected) in [
+            ("datetime_unix(0) < datetime_unix(1)", true),
+            ("datetime_unix(0) >= datetime_unix(1)", false),
+            (
+                "datetime_rfc3339(\"2000-01-01T02:00:00+02:00\") == datetime_rfc3339(\"2000-01-01T00:00:00Z\")",
+                true,
+            ),
+            ("timedelta_seconds(1) != timedelta_seconds(1)", false),
+            ("timedelta_seconds(2) > timedelta_seconds(1)", true),
+            ("timedelta_seconds(2) <= timedelta_seconds(1)", false),
+            (
+                "datetime_millis(1000).approx_eq(datetime_millis(1400), timedelta_millis(500))",
+                true,
+            ),
+            (
+                "datetime_millis(1000).approx_eq(datetime_millis(1600), timedelta_millis(500))",
+                false,
+            ),
+            (
+                "timedelta_millis(-100).approx_eq(timedelta_millis(100), timedelta_millis(200))",
+                true,
+            ),
+            ("timedelta_max().approx_eq(timedelta_min(), timedelta_seconds(1))", false),
+        ] {
+            assert_eq!(
+                engine.eval::<bool>(script).unwrap_or(!expected),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        assert!(
+            engine.eval::<rhai::Dynamic>("min([])").is_ok_and(|result| result.is_unit()),
+            "we should be getting unit from an empty array"
+        );
+
+        for script in [
+            "min([datetime_unix(0), timedelta_zero()])",
+            "max([1, 2])",
+            "timedelta_zero().clamp(timedelta_seconds(10), timedelta_seconds(0))",
+        ] {
+            assert!(
+                engine.eval::<rhai::Dynamic>(script).is_err(),
+                "we should be getting an error from {}",
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn aggregates_work() {
+        let engine = get_engine();
+
+        for (script, expected) in [
+            (
+                "let a = [datetime_unix(30), datetime_unix(10), datetime_unix(20)]; a.sort(); a.map(|dt| dt.timestamp())",
+                vec![10, 20, 30],
+            ),
+            (
+                "let a = [timedelta_seconds(30), timedelta_seconds(-10), timedelta_seconds(20)]; a.sort(); a.map(|td| td.seconds)",
+                vec![-10, 20, 30],
+            ),
+            (
+                "let a = [timedelta_seconds(30), timedelta_seconds(-10), timedelta_seconds(20)]; a.sort_desc(); a.map(|td| td.seconds)",
+                vec![30, 20, -10],
+            ),
+            ("let a = [3, 1, 2]; a.sort(); a", vec![1, 2, 3]),
+            ("let a = [3, 1, 2]; a.sort_desc(); a", vec![3, 2, 1]),
+            ("let a = ['c', 'a', 'b']; a.sort(); a.map(|c| c.to_int())", vec![97, 98, 99]),
+            (r#"let a = ["bb", "c", "a"]; a.sort(); a.map(|s| s.len)"#, vec![1, 2, 1]),
+            ("let a = [3, 1, 2]; a.sort(|x, y| y - x); a", vec![3, 2, 1]),
+            (r#"let a = ["bb", "c", "a"]; a.sort_desc(); a.map(|s| s.len)"#, vec![1, 2, 1]),
+            ("let a = [(), ()]; a.sort(); a.map(|u| 0)", vec![0, 0]),
+            #[cfg(not(feature = "no_float"))]
+            (
+                "let a = [2.5, -1.0, 0.5]; a.sort(); a.map(|f| (f * 10.0).to_int())",
+                vec![-10, 5, 25],
+            ),
+        ] {
+            assert_eq!(
+                engine
+                    .eval::<rhai::Array>(script)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|v| v.as_int().unwrap_or_default())
+                    .collect::<Vec<_>>(),
+                expected,
+                "we should be getting {:?} from {}",
+                expected,
+                script
+            );
+        }
+
+        for (script, expected) in [
+            ("earliest([datetime_unix(30), datetime_unix(10), datetime_unix(20)]).timestamp()", 10),
+            ("latest([datetime_unix(30), datetime_unix(10), datetime_unix(20)]).timestamp()", 30),
+            ("sum([timedelta_seconds(30), timedelta_seconds(-10), timedelta_seconds(20)]).seconds", 40),
+            ("sum([]).seconds", 0),
+            ("mean([timedelta_seconds(1), timedelta_seconds(2), timedelta_seconds(4)]).milliseconds", 2333),
+            ("median([timedelta_seconds(7), timedelta_seconds(1), timedelta_seconds(4)]).seconds", 4),
+            ("median([timedelta_seconds(7), timedelta_seconds(1), timedelta_seconds(4), timedelta_seconds(2)]).seconds", 3),
+            ("percentile([timedelta_seconds(10), timedelta_seconds(20), timedelta_seconds(30)], 0).seconds", 10),
+            ("percentile([timedelta_seconds(10), timedelta_seconds(20), timedelta_seconds(30)], 75).seconds", 25),
+            ("percentile([timedelta_seconds(10), timedelta_seconds(20), timedelta_seconds(30)], 100).seconds", 30),
+            ("stddev([timedelta_seconds(2), timedelta_seconds(4), timedelta_seconds(4), timedelta_seconds(4), timedelta_seconds(5), timedelta_seconds(5), timedelta_seconds(7), timedelta_seconds(9)]).seconds", 2),
+        ] {
+            assert_eq!(
+                engine.eval::<rhai::INT>(script).unwrap_or(-1),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        #[cfg(not(feature = "no_float"))]
+        assert_eq!(
+            engine
+                .eval::<rhai::INT>("percentile([timedelta_seconds(0), timedelta_seconds(1)], 12.5).milliseconds")
+                .unwrap_or_default(),
+            125,
+            "we should be getting a fractional percentile"
+        );
+
+        for script in ["earliest([])", "mean([])", "median([])", "percentile([], 50)", "stddev([])"] {
+            assert!(
+                engine.eval::<rhai::Dynamic>(script).is_ok_and(|result| result.is_unit()),
+                "we should be getting unit from {}",
+                script
+            );
+        }
+
+        for script in [
+            "sum([datetime_unix(0)])",
+            "earliest([timedelta_zero()])",
+            "percentile([timedelta_zero()], 101)",
+        ] {
+            assert!(
+                engine.eval::<rhai::Dynamic>(script).is_err(),
+                "we should be getting an error from {}",
+                script
+            );
+        }
+
+        for (script, expected) in [
+            (
+                "let a = [datetime_unix(0), timedelta_zero()]; a.sort()",
+                "elements of different types cannot be sorted",
+            ),
+            (
+                "let a = [1, datetime_unix(0)]; a.sort()",
+                "elements of different types cannot be sorted",
+            ),
+            (
+                "let a = [datetime_unix(0), 1]; a.sort_desc()",
+                "elements of different types cannot be sorted",
+            ),
+            ("let a = [#{}, #{}]; a.sort()", "elements of map cannot be sorted"),
+        ] {
+            assert!(
+                engine
+                    .eval::<rhai::Dynamic>(script)
+                    .is_err_and(|e| e.to_string().contains(expected)),
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn buckets_work() {
+        let engine = get_engine();
+
+        for (script, expected) in [
+            (
+                r#"datetime_rfc3339("2024-05-10T10:37:12Z").bucket("15m")"#,
+                "2024-05-10T10:30:00+00:00",
+            ),
+            (
+                r#"datetime_rfc3339("2024-05-10T10:37:12Z").bucket(timedelta_minutes(5))"#,
+                "2024-05-10T10:35:00+00:00",
+            ),
+            (
+                r#"datetime_rfc3339("2024-05-10T10:37:12+05:30").bucket("1h")"#,
+                "2024-05-10T10:00:00+05:30",
+            ),
+            (
+                r#"datetime_rfc3339("2024-05-10T23:30:00-04:00").bucket("day")"#,
+                "2024-05-10T00:00:00-04:00",
+            ),
+            (
+                r#"datetime_rfc3339("2024-05-10T23:30:00Z").bucket("week")"#,
+                "2024-05-06T00:00:00+00:00",
+            ),
+            (
+                r#"datetime_rfc3339("2024-03-31T22:30:00Z").bucket("month", "Europe/Berlin")"#,
+                "2024-04-01T00:00:00+02:00",
+            ),
+            (
+                r#"datetime_rfc3339("2024-03-31T12:00:00Z").bucket("day", "Europe/Berlin")"#,
+                "2024-03-31T00:00:00+01:00",
+            ),
+            (
+                r#"datetime_rfc3339("2024-03-31T12:10:00Z").bucket(timedelta_hours(1), "Europe/Berlin")"#,
+                "2024-03-31T14:00:00+02:00",
+            ),
+            (
+                "datetime_unix(100).bucket(timedelta_seconds(60), datetime_unix(30))",
+                "1970-01-01T00:01:30+00:00",
+            ),
+            (
+                "datetime_unix(10).bucket(\"1 minute\", datetime_unix(30))",
+                "1969-12-31T23:59:30+00:00",
+            ),
+        ] {
+            assert_eq!(
+                engine.eval::<String>(&format!("{}.to_string()", script)).unwrap_or_default(),
+                expected,
+                "we should be getting {} from {}",
+                expected,
+                script
+            );
+        }
+
+        for script in [
+            "datetime_unix(0).bucket(\"day\", datetime_unix(0))",
+            "datetime_unix(0).bucket(timedelta_zero())",
+            "datetime_unix(0).bucket(\"-5m\")",
+            "datetime_unix(0).bucket(\"day\", \"Mars/Olympus_Mons\")",
+            "datetime_unix(0).bucket(\"fortnightly\")",
+            "group_by_bucket([#{ at: 1 }], \"day\", \"at\")",
+        ] {
+            assert!(
+                engine.eval::<rhai::Dynamic>(script).is_err(),
+                "we should be getting an error from {}",
+                script
+            );
+        }
+
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                        let events = [
+                            #{ name: "a", at: datetime_rfc3339("2024-03-30T23:30:00Z") },
+                            #{ name: "b", at: datetime_rfc3339("2024-03-30T22:30:00Z") },
+                            #{ name: "c", at: datetime_rfc3339("2024-03-31T05:00:00Z") },
+                            #{ name: "d", at: datetime_rfc3339("2024-04-01T00:15:00+02:00") },
+                        ];
+                        let groups = group_by_bucket(events, "day", "at", "Europe/Berlin");
+                        let out = [];
+                        for key in groups.keys() {
+                            out.push(`${key}=${groups[key].map(|e| e.name)}`);
+                        }
+                        out.reduce(|sum, s| sum + " " + s, "")
+                    "#
+                )
+                .unwrap_or_default(),
+            " 2024-03-29T23:00:00Z=[\"b\"] 2024-03-30T23:00:00Z=[\"a\", \"c\"] 2024-03-31T22:00:00Z=[\"d\"]",
+            "we should be getting events grouped per Berlin day"
+        );
+
+        assert_eq!(
+            engine
+                .eval::<String>(
+                    r#"
+                        let events = [
+                            #{ name: "a", at: datetime_rfc3339("2024-10-27T01:40:00Z") },
+                            #{ name: "b", at: datetime_rfc3339("2024-10-27T00:30:00Z") },
+                            #{ name: "c", at: datetime_rfc3339("2024-10-27T01:10:00Z") },
+                            #{ name: "d", at: datetime_rfc3339("2024-10-27T00:50:00Z") },
+                        ];
+                        let groups = group_by_bucket(events, "1h", "at", "Europe/Berlin");
+                        let out = [];
+                        for key in groups.keys() {
+                            out.push(`${key}=${groups[key].map(|e| e.name)}`);
+                        }
+                        out.reduce(|sum, s| sum + " " + s, "")
+                    "#
+                )
+                .unwrap_or_default(),
+            " 2024-10-27T00:00:00Z=[\"b\", \"d\"] 2024-10-27T01:00:00Z=[\"a\", \"c\"]",
+            "we should be getting the repeated Berlin hour as two groups in chronological order"
+        );
+
+        assert_eq!(
+            engine
+                .eval::<rhai::Array>(
+                    r#"
+                        let groups = group_by_bucket([
+                            datetime_rfc3339("2024-01-01T10:15:00Z"),
+                            datetime_rfc3339("2024-01-01T11:20:00+01:00"),
+                            datetime_rfc3339("2024-01-01T11:45:00Z"),
+                        ], timedelta_hours(1));
+                        let out = [];
+                        for key in groups.keys() {
+                            out.push(`${key}:${groups[key].len()}`);
+                        }
+                        out
+                    "#
+                )
+                .unwrap_or_default()
+                .into_iter()
+                .map(|group| group.to_string())
+                .collect::<Vec<_>>(),
+            vec!["2024-01-01T10:00:00Z:2", "2024-01-01T11:00:00Z:1"],
+            "we should be getting one group for the same bucket start seen in different offsets"
+        );
+
+        assert_eq!(
+            engine
+                .eval::<rhai::Array>(r#"group_by_bucket([datetime_millis(1500), datetime_millis(200)], timedelta_millis(500)).keys()"#)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|key| key.to_string())
+                .collect::<Vec<_>>(),
+            vec!["1970-01-01T00:00:00.000000000Z", "1970-01-01T00:00:01.500000000Z"],
+            "we should be getting fixed width keys for sub-second buckets"
+        );
+    }
 }
@@ -0,0 +1,455 @@
+use chrono::{DateTime, Datelike, FixedOffset, Months, NaiveTime, TimeDelta, Weekday};
+use rhai::{EvalAltResult, Map};
+use std::str::FromStr;
+
+use crate::calendar::{out_of_range, with_local};
+
+/// Phrases of another language paired with the English words they stand for
+pub type WordTable = Vec<(Vec<String>, Vec<String>)>;
+
+/// Build a word table from a map of phrases to English words, i.e. `#{ "demain": "tomorrow", "il y a": "-" }`
+pub fn word_table(words: Map) -> Result<WordTable, Box<EvalAltResult>> {
+    let mut table: WordTable = Vec::with_capacity(words.len());
+
+    for (phrase, english) in words {
+        let english = english
+            .into_immutable_string()
+            .map_err(|_e| Box::<EvalAltResult>::from(format!("Word table value for {} must be a string", phrase)))?;
+
+        let phrase: Vec<String> = phrase.split_whitespace().map(str::to_lowercase).collect();
+
+        if !phrase.is_empty() {
+            table.push((phrase, english.split_whitespace().map(str::to_lowercase).collect()));
+        }
+    }
+
+    // longest first so "après demain" wins over "demain"
+    table.sort_by_key(|(phrase, _)| std::cmp::Reverse(phrase.len()));
+
+    Ok(table)
+}
+
+/// Lowercase word of the expression and the byte offset it starts at
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    offset: usize,
+}
+
+fn tokenize(expr: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (i, c) in expr.char_indices().chain(std::iter::once((expr.len(), ' '))) {
+        let separator = c.is_whitespace() || c == ',';
+
+        match (start, separator) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                tokens.push(Token {
+                    text: expr[s..i].to_lowercase(),
+                    offset: s,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+/// Replace phrases of the word table with their English words, keeping the offset of the original phrase
+fn translate(tokens: Vec<Token>, words: &WordTable) -> Vec<Token> {
+    let mut translated = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let found = words.iter().find(|(phrase, _)| {
+            tokens.len() - i >= phrase.len() && phrase.iter().zip(&tokens[i..]).all(|(word, token)| *word == token.text)
+        });
+
+        match found {
+            Some((phrase, english)) => {
+                let offset = tokens[i].offset;
+
+                translated.extend(english.iter().map(|text| Token {
+                    text: text.clone(),
+                    offset,
+                }));
+
+                i += phrase.len();
+            }
+            None => {
+                translated.push(tokens[i].clone());
+                i += 1;
+            }
+        }
+    }
+
+    translated
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// Unit name and how many of the unit it stands for, so "fortnight" is two weeks
+fn parse_unit(word: &str) -> Option<(Unit, i64)> {
+    let unit = match word {
+        "second" | "seconds" | "sec" | "secs" => (Unit::Second, 1),
+        "minute" | "minutes" | "min" | "mins" => (Unit::Minute, 1),
+        "hour" | "hours" | "hr" | "hrs" => (Unit::Hour, 1),
+        "day" | "days" => (Unit::Day, 1),
+        "week" | "weeks" => (Unit::Week, 1),
+        "fortnight" | "fortnights" => (Unit::Week, 2),
+        "month" | "months" => (Unit::Month, 1),
+        "quarter" | "quarters" => (Unit::Month, 3),
+        "year" | "years" => (Unit::Year, 1),
+        "decade" | "decades" => (Unit::Year, 10),
+        _ => return None,
+    };
+
+    Some(unit)
+}
+
+/// Direction of "next", "last" and "this"
+fn parse_modifier(word: &str) -> Option<i64> {
+    match word {
+        "next" => Some(1),
+        "last" | "previous" => Some(-1),
+        "this" => Some(0),
+        _ => None,
+    }
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    if word.len() < 3 {
+        return None;
+    }
+
+    Weekday::from_str(word).ok()
+}
+
+/// Clock time such as "9am", "9:30 pm" or "21:00", with the number of tokens it spans
+fn parse_clock(word: &str, next: Option<&str>) -> Option<(NaiveTime, usize)> {
+    let word = word.replace('.', "");
+    let next = next.map(|n| n.replace('.', ""));
+
+    let (clock, suffix, consumed) = if let Some(clock) = word.strip_suffix("am") {
+        (clock.to_string(), Some(false), 1)
+    } else if let Some(clock) = word.strip_suffix("pm") {
+        (clock.to_string(), Some(true), 1)
+    } else {
+        match next.as_deref() {
+            Some("am") => (word, Some(false), 2),
+            Some("pm") => (word, Some(true), 2),
+            _ => (word, None, 1),
+        }
+    };
+
+    // a bare number is a count, not a time
+    if suffix.is_none() && !clock.contains(':') {
+        return None;
+    }
+
+    let parts: Vec<u32> = clock
+        .split(':')
+        .map(|part| {
+            if !part.is_empty() && part.len() <= 2 && part.chars().all(|c| c.is_ascii_digit()) {
+                part.parse().ok()
+            } else {
+                None
+            }
+        })
+        .collect::<Option<Vec<u32>>>()?;
+
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let hour = match (parts[0], suffix) {
+        (12, Some(false)) => 0,
+        (1..=11, Some(true)) => parts[0] + 12,
+        (1..=12, Some(_)) => parts[0],
+        (_, Some(_)) => return None,
+        (hour, None) => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, *parts.get(1).unwrap_or(&0), *parts.get(2).unwrap_or(&0)).map(|time| (time, consumed))
+}
+
+fn shift(dt: DateTime<FixedOffset>, unit: Unit, count: i64) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+    let months = match unit {
+        Unit::Month => Some(count),
+        Unit::Year => count.checked_mul(12),
+        _ => None,
+    };
+
+    let shifted = match (unit, months) {
+        (Unit::Month | Unit::Year, Some(months)) => u32::try_from(months.unsigned_abs()).ok().and_then(|m| {
+            if months < 0 {
+                dt.checked_sub_months(Months::new(m))
+            } else {
+                dt.checked_add_months(Months::new(m))
+            }
+        }),
+        (Unit::Second, _) => TimeDelta::try_seconds(count).and_then(|td| dt.checked_add_signed(td)),
+        (Unit::Minute, _) => TimeDelta::try_minutes(count).and_then(|td| dt.checked_add_signed(td)),
+        (Unit::Hour, _) => TimeDelta::try_hours(count).and_then(|td| dt.checked_add_signed(td)),
+        (Unit::Day, _) => TimeDelta::try_days(count).and_then(|td| dt.checked_add_signed(td)),
+        (Unit::Week, _) => TimeDelta::try_weeks(count).and_then(|td| dt.checked_add_signed(td)),
+        _ => None,
+    };
+
+    shifted.ok_or_else(out_of_range)
+}
+
+/// Parser state for a relative date expression applied on top of a base DateTime
+struct Parser<'a> {
+    expr: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    /// explicit time of day, kept when a later word moves to another day
+    time: Option<NaiveTime>,
+}
+
+impl Parser<'_> {
+    fn peek(&self, ahead: usize) -> Option<&str> {
+        self.tokens.get(self.pos + ahead).map(|t| t.text.as_str())
+    }
+
+    fn error(&self, expected: &str) -> Box<EvalAltResult> {
+        let offset = self.tokens.get(self.pos).map_or(self.expr.len(), |t| t.offset);
+        let position = self.expr[..offset].chars().count();
+        let rest = self.expr[offset..].trim();
+
+        let found = if rest.is_empty() {
+            "end of input".to_string()
+        } else {
+            format!("\"{}\"", rest)
+        };
+
+        Box::<EvalAltResult>::from(format!(
+            "Failed to parse relative date \"{}\": expected {} at position {}, found {}",
+            self.expr, expected, position, found
+        ))
+    }
+
+    /// Start of the day, or the explicit time of day if one was given
+    fn day(&self, dt: DateTime<FixedOffset>) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+        with_local(&dt, dt.naive_local().date().and_time(self.time.unwrap_or(NaiveTime::MIN)))
+    }
+
+    fn count(&mut self) -> Result<i64, Box<EvalAltResult>> {
+        let (sign, word) = match self.peek(0) {
+            Some("+") => (1, self.peek(1).map(|w| (w.to_string(), 2))),
+            Some("-") => (-1, self.peek(1).map(|w| (w.to_string(), 2))),
+            Some(word) => (1, Some((word.to_string(), 1))),
+            None => (1, None),
+        };
+
+        let (word, consumed) = word.ok_or_else(|| self.error("a number"))?;
+
+        let count = match word.as_str() {
+            "a" | "an" | "one" => Some(1),
+            _ => word.strip_prefix('+').unwrap_or(&word).parse::<i64>().ok(),
+        };
+
+        let count = count.ok_or_else(|| {
+            self.pos += consumed - 1;
+            self.error("a number")
+        })?;
+
+        self.pos += consumed;
+
+        Ok(sign * count)
+    }
+
+    fn unit(&mut self) -> Result<(Unit, i64), Box<EvalAltResult>> {
+        let unit = self.peek(0).and_then(parse_unit).ok_or_else(|| self.error("a unit"))?;
+
+        self.pos += 1;
+
+        Ok(unit)
+    }
+
+    /// Signed count of units, i.e. "3 days", "+2 weeks", "a month ago" or "5 minutes from now"
+    fn duration(&mut self) -> Result<(Unit, i64), Box<EvalAltResult>> {
+        let count = self.count()?;
+        let (unit, multiplier) = self.unit()?;
+        let mut count = count.checked_mul(multiplier).ok_or_else(out_of_range)?;
+
+        match (self.peek(0), self.peek(1)) {
+            (Some("ago"), _) => {
+                count = -count;
+                self.pos += 1;
+            }
+            (Some("later"), _) => self.pos += 1,
+            (Some("from"), Some("now")) => self.pos += 2,
+            _ => {}
+        }
+
+        Ok((unit, count))
+    }
+
+    /// Period after "first day of" or "last day of", i.e. "next month", "month" or "+2 years"
+    fn period(&mut self) -> Result<(Unit, i64, usize), Box<EvalAltResult>> {
+        let start = self.pos;
+
+        let (unit, count) = if let Some(direction) = self.peek(0).and_then(parse_modifier) {
+            self.pos += 1;
+            let (unit, multiplier) = self.unit()?;
+            (unit, direction * multiplier)
+        } else if let Some((unit, _)) = self.peek(0).and_then(parse_unit) {
+            self.pos += 1;
+            (unit, 0)
+        } else {
+            if self.peek(0) == Some("in") {
+                self.pos += 1;
+            }
+            self.duration()?
+        };
+
+        Ok((unit, count, start))
+    }
+
+    /// Closest given weekday after (1), before (-1) or on or after (0) the date
+    fn weekday(&self, dt: DateTime<FixedOffset>, weekday: Weekday, direction: i64) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+        let from = dt.weekday().num_days_from_monday() as i64;
+        let to = weekday.num_days_from_monday() as i64;
+
+        let days = match direction {
+            1 if from == to => 7,
+            -1 if from == to => -7,
+            -1 => -(from - to).rem_euclid(7),
+            _ => (to - from).rem_euclid(7),
+        };
+
+        self.day(shift(dt, Unit::Day, days)?)
+    }
+
+    fn parse(mut self, base: DateTime<FixedOffset>) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+        if self.tokens.is_empty() {
+            return Err(self.error("a relative date"));
+        }
+
+        let mut dt = base;
+
+        while let Some(word) = self.peek(0).map(str::to_string) {
+            match word.as_str() {
+                "now" | "at" | "on" | "and" => self.pos += 1,
+                "today" => {
+                    self.pos += 1;
+                    dt = self.day(dt)?;
+                }
+                "tomorrow" => {
+                    self.pos += 1;
+                    dt = self.day(shift(dt, Unit::Day, 1)?)?;
+                }
+                "yesterday" => {
+                    self.pos += 1;
+                    dt = self.day(shift(dt, Unit::Day, -1)?)?;
+                }
+                "noon" | "midday" | "midnight" => {
+                    self.pos += 1;
+                    self.time = NaiveTime::from_hms_opt(if word == "midnight" { 0 } else { 12 }, 0, 0);
+                    dt = self.day(dt)?;
+                }
+                "in" => {
+                    self.pos += 1;
+                    let (unit, count) = self.duration()?;
+                    dt = shift(dt, unit, count)?;
+                }
+                "first" | "last" if self.peek(1) == Some("day") && self.peek(2) == Some("of") => {
+                    self.pos += 3;
+                    let (unit, count, start) = self.period()?;
+
+                    let unit_name = match unit {
+                        Unit::Week => "week",
+                        Unit::Month => "month",
+                        Unit::Year => "year",
+                        _ => {
+                            self.pos = start;
+                            return Err(self.error("week, month or year"));
+                        }
+                    };
+
+                    let shifted = shift(dt, unit, count)?;
+                    let snapped = if word == "first" {
+                        crate::calendar::start_of(&shifted, unit_name)?
+                    } else {
+                        crate::calendar::end_of(&shifted, unit_name)?
+                    };
+
+                    dt = self.day(snapped)?;
+                }
+                _ => {
+                    if let Some(direction) = parse_modifier(&word) {
+                        self.pos += 1;
+
+                        if let Some(weekday) = self.peek(0).and_then(parse_weekday) {
+                            self.pos += 1;
+                            dt = self.weekday(dt, weekday, direction)?;
+                        } else {
+                            let (unit, multiplier) = self.unit()?;
+                            dt = shift(dt, unit, direction * multiplier)?;
+
+                            // "next day" and "last day" are day words like "tomorrow" and "yesterday"
+                            if unit == Unit::Day {
+                                dt = self.day(dt)?;
+                            }
+                        }
+                    } else if let Some(weekday) = parse_weekday(&word) {
+                        self.pos += 1;
+
+                        // postfix modifier as in "friday next", for word tables of languages that put it last
+                        let direction = self.peek(0).and_then(parse_modifier);
+
+                        if direction.is_some() {
+                            self.pos += 1;
+                        }
+
+                        dt = self.weekday(dt, weekday, direction.unwrap_or(0))?;
+                    } else if let Some((time, consumed)) = parse_clock(&word, self.peek(1)) {
+                        self.pos += consumed;
+                        self.time = Some(time);
+                        dt = self.day(dt)?;
+                    } else {
+                        let (unit, count) = self.duration().map_err(|e| {
+                            if self.peek(0) == Some(word.as_str()) {
+                                self.error("a relative date")
+                            } else {
+                                e
+                            }
+                        })?;
+                        dt = shift(dt, unit, count)?;
+                    }
+                }
+            }
+        }
+
+        Ok(dt)
+    }
+}
+
+/// Apply an English relative date expression such as "tomorrow 9am", "next friday" or "last day of next month" to the base
+///
+/// Words of other languages are first replaced through the word table, so the expression must follow English word order
+/// except for weekday modifiers, which may come after the weekday.
+pub fn parse_relative(expr: &str, base: DateTime<FixedOffset>, words: &WordTable) -> Result<DateTime<FixedOffset>, Box<EvalAltResult>> {
+    Parser {
+        expr,
+        tokens: translate(tokenize(expr), words),
+        pos: 0,
+        time: None,
+    }
+    .parse(base)
+}
@@ -13,12 +13,460 @@ pub fn borrow_mut(td: &Shared<Locked<TimeDelta>>) -> impl DerefMut<Target = Time
     return td.write().unwrap();
 }
 
+/// Fixed length units of human readable durations, largest first, as compact symbol, English names and length in nanoseconds
+const HUMAN_UNITS: [(&str, &str, &str, i128); 7] = [
+    ("d", "day", "days", 86_400_000_000_000),
+    ("h", "hour", "hours", 3_600_000_000_000),
+    ("m", "minute", "minutes", 60_000_000_000),
+    ("s", "second", "seconds", 1_000_000_000),
+    ("ms", "millisecond", "milliseconds", 1_000_000),
+    ("µs", "microsecond", "microseconds", 1_000),
+    ("ns", "nanosecond", "nanoseconds", 1),
+];
+
+/// Length in nanoseconds of a Go-style or spelled-out unit name
+fn human_unit(word: &str) -> Option<i128> {
+    let nanos = match word.to_lowercase().as_str() {
+        "w" | "wk" | "wks" | "week" | "weeks" => 604_800_000_000_000,
+        "d" | "day" | "days" => 86_400_000_000_000,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3_600_000_000_000,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60_000_000_000,
+        "s" | "sec" | "secs" | "second" | "seconds" => 1_000_000_000,
+        "ms" | "msec" | "msecs" | "millis" | "millisecond" | "milliseconds" => 1_000_000,
+        "us" | "µs" | "μs" | "micros" | "microsecond" | "microseconds" => 1_000,
+        "ns" | "nanos" | "nanosecond" | "nanoseconds" => 1,
+        _ => return None,
+    };
+
+    Some(nanos)
+}
+
+/// Total nanoseconds, which always fit i128
+pub fn total_nanos(td: &TimeDelta) -> i128 {
+    td.num_seconds() as i128 * 1_000_000_000 + td.subsec_nanos() as i128
+}
+
+/// TimeDelta from total nanoseconds, if within range
+pub fn from_total_nanos(nanos: i128) -> Option<TimeDelta> {
+    let secs = i64::try_from(nanos.div_euclid(1_000_000_000)).ok()?;
+
+    TimeDelta::new(secs, nanos.rem_euclid(1_000_000_000) as u32)
+}
+
+/// Parse a duration such as "90s", "1h30m", "1.5h", "-300ms" or "2 days, 3 hours and 4 minutes"
+pub fn parse_human(duration: &str) -> Result<TimeDelta, Box<EvalAltResult>> {
+    let chars: Vec<(usize, char)> = duration.char_indices().collect();
+    let mut i = 0;
+
+    let error = |i: usize, expected: &str| {
+        let offset = chars.get(i).map_or(duration.len(), |(offset, _)| *offset);
+        let rest = duration[offset..].trim();
+
+        let found = if rest.is_empty() {
+            "end of input".to_string()
+        } else {
+            format!("\"{}\"", rest)
+        };
+
+        Box::<EvalAltResult>::from(format!(
+            "Failed to parse duration \"{}\": expected {} at position {}, found {}",
+            duration, expected, i, found
+        ))
+    };
+
+    let skip = |i: &mut usize, accept: &dyn Fn(char) -> bool| {
+        let start = *i;
+
+        while *i < chars.len() && accept(chars[*i].1) {
+            *i += 1;
+        }
+
+        chars[start..*i].iter().map(|(_, c)| *c).collect::<String>()
+    };
+
+    skip(&mut i, &char::is_whitespace);
+
+    let negative = chars.get(i).is_some_and(|(_, c)| *c == '-');
+
+    if matches!(chars.get(i).map(|(_, c)| *c), Some('-' | '+')) {
+        i += 1;
+    }
+
+    let mut total: i128 = 0;
+    let mut components = 0;
+
+    loop {
+        skip(&mut i, &|c| c.is_whitespace() || c == ',');
+
+        let and_follows = chars
+            .get(i..i + 3)
+            .is_some_and(|w| w.iter().map(|(_, c)| c.to_ascii_lowercase()).eq("and".chars()))
+            && chars.get(i + 3).is_none_or(|(_, c)| !c.is_alphabetic());
+
+        if components > 0 && and_follows {
+            i += 3;
+            continue;
+        }
+
+        if i >= chars.len() {
+            break;
+        }
+
+        let number_start = i;
+        let whole = skip(&mut i, &|c| c.is_ascii_digit());
+
+        let fraction = if chars.get(i).is_some_and(|(_, c)| *c == '.') {
+            i += 1;
+            skip(&mut i, &|c| c.is_ascii_digit())
+        } else {
+            String::new()
+        };
+
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(error(number_start, "a number"));
+        }
+
+        skip(&mut i, &char::is_whitespace);
+
+        let unit_start = i;
+        let word = skip(&mut i, &|c| c.is_alphabetic());
+
+        let unit = match human_unit(&word) {
+            Some(unit) => unit,
+            // Go accepts a lone zero without unit
+            None if word.is_empty() && components == 0 && i >= chars.len() && whole.chars().chain(fraction.chars()).all(|c| c == '0') => 0,
+            None => return Err(error(unit_start, "a unit")),
+        };
+
+        let whole: i128 = if whole.is_empty() { Some(0) } else { whole.parse().ok() }
+            .ok_or_else(|| Box::<EvalAltResult>::from("Delta out of range".to_string()))?;
+
+        // digits beyond what the unit can carry in nanoseconds are dropped
+        let digits = &fraction[..fraction.len().min(18)];
+        let fraction: i128 = if digits.is_empty() {
+            0
+        } else {
+            digits.parse::<i128>().unwrap_or(0) * unit / 10_i128.pow(digits.len() as u32)
+        };
+
+        total = whole
+            .checked_mul(unit)
+            .and_then(|nanos| nanos.checked_add(fraction))
+            .and_then(|nanos| total.checked_add(nanos))
+            .ok_or_else(|| Box::<EvalAltResult>::from("Delta out of range".to_string()))?;
+
+        components += 1;
+    }
+
+    if components == 0 {
+        return Err(error(i, "a duration"));
+    }
+
+    from_total_nanos(if negative { -total } else { total }).ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+}
+
+/// Non-zero parts of the absolute duration as indexes into `HUMAN_UNITS` with their amounts, largest first
+fn human_parts(td: &TimeDelta) -> Vec<(usize, i128)> {
+    let mut rest = total_nanos(td).abs();
+
+    HUMAN_UNITS
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (_, _, _, nanos))| {
+            let amount = rest / nanos;
+            rest %= nanos;
+
+            (amount > 0).then_some((index, amount))
+        })
+        .collect()
+}
+
+/// Format as "1h 30m", using units from days down to nanoseconds
+pub fn format_compact(td: &TimeDelta) -> String {
+    let parts = human_parts(td);
+
+    if parts.is_empty() {
+        return "0s".to_string();
+    }
+
+    let sign = if *td < TimeDelta::zero() { "-" } else { "" };
+    let parts: Vec<String> = parts
+        .iter()
+        .map(|(index, amount)| format!("{}{}", amount, HUMAN_UNITS[*index].0))
+        .collect();
+
+    format!("{}{}", sign, parts.join(" "))
+}
+
+/// Format as "1 hour, 30 minutes" with at most `max_units` of the largest non-zero units
+///
+/// Unit names and separators come from `words`, i.e. `#{ "hour": "Stunde", "hours": "Stunden", "last_separator": " und " }`,
+/// falling back to English.
+pub fn format_verbose(td: &TimeDelta, max_units: rhai::INT, words: &rhai::Map) -> Result<String, Box<EvalAltResult>> {
+    if max_units < 1 {
+        return Err(Box::<EvalAltResult>::from("Maximum number of units must be positive".to_string()));
+    }
+
+    let word = |key: &str| -> Result<String, Box<EvalAltResult>> {
+        match words.get(key) {
+            Some(value) => value
+                .clone()
+                .into_string()
+                .map_err(|_e| Box::<EvalAltResult>::from(format!("Word table value for {} must be a string", key))),
+            None => Ok(match key {
+                "separator" | "last_separator" => ", ".to_string(),
+                _ => key.to_string(),
+            }),
+        }
+    };
+
+    let mut parts = human_parts(td);
+    parts.truncate(max_units as usize);
+
+    if parts.is_empty() {
+        return Ok(format!("0 {}", word("seconds")?));
+    }
+
+    let parts = parts
+        .iter()
+        .map(|(index, amount)| {
+            let (_, singular, plural, _) = HUMAN_UNITS[*index];
+
+            Ok(format!("{} {}", amount, word(if *amount == 1 { singular } else { plural })?))
+        })
+        .collect::<Result<Vec<String>, Box<EvalAltResult>>>()?;
+
+    let sign = if *td < TimeDelta::zero() { "-" } else { "" };
+
+    let text = match parts.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{}{}{}", rest.join(&word("separator")?), word("last_separator")?, last),
+        _ => parts.join(""),
+    };
+
+    Ok(format!("{}{}", sign, text))
+}
+
+/// Parse a clock-style duration such as "01:23:45.678", "-00:05:00" or "3:45", hours being unbounded
+pub fn parse_clock(clock: &str) -> Result<TimeDelta, Box<EvalAltResult>> {
+    let error = || {
+        Box::<EvalAltResult>::from(format!(
+            "Failed to parse clock duration {}. Expected [-]hh:mm:ss[.fff] or mm:ss[.fff]",
+            clock
+        ))
+    };
+
+    let trimmed = clock.trim();
+    let (negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+    if fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return Err(error());
+    }
+
+    let parts: Vec<&str> = whole.split(':').collect();
+
+    if !(2..=3).contains(&parts.len()) || parts.iter().any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit())) {
+        return Err(error());
+    }
+
+    let out_of_range = || Box::<EvalAltResult>::from("Delta out of range".to_string());
+
+    // parts are all digits by now, so only their length can make parsing fail
+    let numbers: Vec<i128> = parts
+        .iter()
+        .map(|p| p.parse::<i128>().map_err(|_e| out_of_range()))
+        .collect::<Result<_, _>>()?;
+
+    // only the leading part may exceed its unit
+    if numbers[1..].iter().any(|n| *n >= 60) || parts[1..].iter().any(|p| p.len() != 2) {
+        return Err(error());
+    }
+
+    let seconds = numbers
+        .iter()
+        .try_fold(0_i128, |total, n| total.checked_mul(60)?.checked_add(*n))
+        .ok_or_else(out_of_range)?;
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        fraction.parse::<i128>().map_err(|_e| error())? * 10_i128.pow(9 - fraction.len() as u32)
+    };
+
+    let total = seconds
+        .checked_mul(1_000_000_000)
+        .and_then(|total| total.checked_add(nanos))
+        .ok_or_else(out_of_range)?;
+
+    from_total_nanos(if negative { -total } else { total }).ok_or_else(out_of_range)
+}
+
+/// Format with a pattern such as "%H:%M:%S%.3f"
+///
+/// `%D` total days, `%H` total hours and `%h` hours of the day, `%M` minutes of the hour, `%S` seconds of the minute,
+/// all zero-padded to two digits, `%m` and `%s` unpadded total minutes and seconds, `%3f`, `%6f`, `%9f` fractional digits,
+/// `%.3f`, `%.6f`, `%.9f` the same with a leading dot, `%.f` only the significant fraction, `%-` sign if negative,
+/// `%+` sign always and `%%` a literal percent sign.
+pub fn format_pattern(td: &TimeDelta, pattern: &str) -> Result<String, Box<EvalAltResult>> {
+    let total = total_nanos(td);
+    let nanos = total.abs();
+    let seconds = nanos / 1_000_000_000;
+    let fraction = nanos % 1_000_000_000;
+
+    let error = |spec: &str| Box::<EvalAltResult>::from(format!("Invalid format string {}: unknown specifier %{}", pattern, spec));
+
+    let mut output = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+
+        let mut spec = String::new();
+
+        for next in chars.by_ref() {
+            spec.push(next);
+
+            if !(next == '.' || next.is_ascii_digit()) {
+                break;
+            }
+        }
+
+        match spec.as_str() {
+            "D" => output.push_str(&format!("{:02}", seconds / 86_400)),
+            "H" => output.push_str(&format!("{:02}", seconds / 3_600)),
+            "h" => output.push_str(&format!("{:02}", seconds / 3_600 % 24)),
+            "M" => output.push_str(&format!("{:02}", seconds / 60 % 60)),
+            "S" => output.push_str(&format!("{:02}", seconds % 60)),
+            "m" => output.push_str(&(seconds / 60).to_string()),
+            "s" => output.push_str(&seconds.to_string()),
+            "3f" | ".3f" | "6f" | ".6f" | "9f" | ".9f" => {
+                let digits = spec.trim_start_matches('.').trim_end_matches('f').parse::<u32>().unwrap_or(9);
+
+                if spec.starts_with('.') {
+                    output.push('.');
+                }
+
+                output.push_str(&format!("{:0width$}", fraction / 10_i128.pow(9 - digits), width = digits as usize));
+            }
+            ".f" if fraction == 0 => {}
+            ".f" => output.push_str(format!(".{:09}", fraction).trim_end_matches('0')),
+            "-" if total < 0 => output.push('-'),
+            "-" => {}
+            "+" => output.push(if total < 0 { '-' } else { '+' }),
+            "%" => output.push('%'),
+            _ => return Err(error(&spec)),
+        }
+    }
+
+    Ok(output)
+}
+
+/// Multiply by an integer factor, if the result is within range
+pub fn scale(td: &TimeDelta, factor: rhai::INT) -> Option<TimeDelta> {
+    total_nanos(td).checked_mul(factor as i128).and_then(from_total_nanos)
+}
+
+/// Round fractional nanoseconds to a delta, if the result is within range
+#[cfg(not(feature = "no_float"))]
+fn from_float_nanos(nanos: rhai::FLOAT) -> Option<TimeDelta> {
+    let nanos = nanos.round();
+
+    if !nanos.is_finite() || nanos.abs() >= i128::MAX as rhai::FLOAT {
+        return None;
+    }
+
+    from_total_nanos(nanos as i128)
+}
+
+/// Multiply by a fractional factor rounded to the nanosecond, if the result is within range
+#[cfg(not(feature = "no_float"))]
+pub fn scale_float(td: &TimeDelta, factor: rhai::FLOAT) -> Option<TimeDelta> {
+    from_float_nanos(total_nanos(td) as rhai::FLOAT * factor)
+}
+
+/// Divide by a fractional divisor rounded to the nanosecond, if the result is within range
+#[cfg(not(feature = "no_float"))]
+pub fn divide_float(td: &TimeDelta, divisor: rhai::FLOAT) -> Option<TimeDelta> {
+    from_float_nanos(total_nanos(td) as rhai::FLOAT / divisor)
+}
+
+/// Bound a result that overflowed in the given direction
+pub fn saturate(negative: bool) -> TimeDelta {
+    if negative {
+        TimeDelta::MIN
+    } else {
+        TimeDelta::MAX
+    }
+}
+
+/// Direction in which to round to a multiple of a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+    HalfUp,
+    HalfEven,
+}
+
+impl Rounding {
+    /// Rounding mode of `round` by name, being "half_up" (ties away from zero) or "half_even" (ties to the even multiple)
+    pub fn from_name(name: &str) -> Result<Self, Box<EvalAltResult>> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "half_up" => Ok(Rounding::HalfUp),
+            "half_even" => Ok(Rounding::HalfEven),
+            _ => Err(Box::<EvalAltResult>::from(format!(
+                "Invalid rounding mode {}. Supported modes are half_up and half_even",
+                name
+            ))),
+        }
+    }
+}
+
+/// Length in nanoseconds of a fixed unit name such as "minute" or "ms"
+pub fn unit_nanos(unit: &str) -> Result<i128, Box<EvalAltResult>> {
+    human_unit(unit.trim()).ok_or(Box::<EvalAltResult>::from(format!("Invalid unit {}", unit)))
+}
+
+/// Round to a multiple of a step given in nanoseconds, if the result is within range
+pub fn round_to(td: &TimeDelta, step: i128, rounding: Rounding) -> Result<TimeDelta, Box<EvalAltResult>> {
+    let step = step.abs();
+
+    if step == 0 {
+        return Err(Box::<EvalAltResult>::from("Rounding step must not be zero".to_string()));
+    }
+
+    let nanos = total_nanos(td);
+    let floor = nanos.div_euclid(step) * step;
+    let rest = nanos - floor;
+
+    let rounded = match rounding {
+        Rounding::Floor => floor,
+        Rounding::Ceil if rest == 0 => floor,
+        Rounding::Ceil => floor + step,
+        _ if rest * 2 < step => floor,
+        _ if rest * 2 > step => floor + step,
+        // exactly half way
+        Rounding::HalfUp if nanos < 0 => floor,
+        Rounding::HalfUp => floor + step,
+        _ if (floor / step) % 2 == 0 => floor,
+        _ => floor + step,
+    };
+
+    from_total_nanos(rounded).ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+}
+
 #[export_module]
 pub mod timedelta_module {
     use chrono::TimeDelta;
 
     use chrono_tz::Tz;
-    use rhai::{EvalAltResult, Locked, Position, Shared};
+    use rhai::{Dynamic, EvalAltResult, Locked, Position, Shared};
 
     /// Alias type to bridge rhai and chrono TimeDelta
     pub type Timedelta = Shared<Locked<TimeDelta>>;
@@ -32,13 +480,13 @@ pub mod timedelta_module {
     /// Construct TimeDelta minimum
     #[rhai_fn(return_raw, name = "timedelta_min")]
     pub fn timedelta_min() -> Result<Timedelta, Box<EvalAltResult>> {
-        Ok(Shared::new(Locked::new(TimeDelta::min_value())))
+        Ok(Shared::new(Locked::new(TimeDelta::MIN)))
     }
 
     /// Construct TimeDelta maximum
     #[rhai_fn(return_raw, name = "timedelta_max")]
     pub fn timedelta_max() -> Result<Timedelta, Box<EvalAltResult>> {
-        Ok(Shared::new(Locked::new(TimeDelta::max_value())))
+        Ok(Shared::new(Locked::new(TimeDelta::MAX)))
     }
 
     /// Construct TimeDelta with number of seconds
@@ -117,6 +565,18 @@ pub mod timedelta_module {
         Shared::new(Locked::new(TimeDelta::nanoseconds(micros)))
     }
 
+    /// Construct TimeDelta from human readable duration such as "90s", "1h30m", "1.5h" or "2 days 3 hours"
+    #[rhai_fn(return_raw, name = "timedelta_parse_human")]
+    pub fn timedelta_parse_human(duration: &str) -> Result<Timedelta, Box<EvalAltResult>> {
+        Ok(Shared::new(Locked::new(parse_human(duration)?)))
+    }
+
+    /// Construct TimeDelta from clock-style duration such as "01:23:45.678" or "-00:05:00"
+    #[rhai_fn(return_raw, name = "timedelta_clock")]
+    pub fn timedelta_clock(clock: &str) -> Result<Timedelta, Box<EvalAltResult>> {
+        Ok(Shared::new(Locked::new(parse_clock(clock)?)))
+    }
+
     /// Check if is zero
     #[rhai_fn(global, get = "is_zero", name = "is_zero", pure)]
     pub fn is_zero(td: &mut Timedelta) -> bool {
@@ -125,12 +585,214 @@ pub mod timedelta_module {
         this.is_zero()
     }
 
-    /// Convert to positive value
+    /// Positive copy of the delta, leaving the original untouched
     #[rhai_fn(global, name = "abs", pure)]
-    pub fn abs(td: &mut Timedelta) {
-        let mut this = borrow_mut(td);
+    pub fn abs(td: &mut Timedelta) -> Timedelta {
+        Shared::new(Locked::new(borrow_mut(td).abs()))
+    }
+
+    /// Negated copy of the delta
+    #[rhai_fn(global, name = "neg", name = "-", pure)]
+    pub fn neg(td: &mut Timedelta) -> Timedelta {
+        Shared::new(Locked::new(-*borrow_mut(td)))
+    }
+
+    /// Check if is shorter than zero
+    #[rhai_fn(global, get = "is_negative", name = "is_negative", pure)]
+    pub fn is_negative(td: &mut Timedelta) -> bool {
+        *borrow_mut(td) < TimeDelta::zero()
+    }
+
+    /// Check if is longer than zero
+    #[rhai_fn(global, get = "is_positive", name = "is_positive", pure)]
+    pub fn is_positive(td: &mut Timedelta) -> bool {
+        *borrow_mut(td) > TimeDelta::zero()
+    }
+
+    /// Sign of the delta as -1, 0 or 1
+    #[rhai_fn(global, get = "signum", name = "signum", pure)]
+    pub fn signum(td: &mut Timedelta) -> rhai::INT {
+        match borrow_mut(td).cmp(&TimeDelta::zero()) {
+            std::cmp::Ordering::Less => -1,
+            std::cmp::Ordering::Equal => 0,
+            std::cmp::Ordering::Greater => 1,
+        }
+    }
+
+    /// Absolute difference between two deltas
+    #[rhai_fn(global, name = "abs_diff", pure, return_raw)]
+    pub fn abs_diff(td: &mut Timedelta, other: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        let this = *borrow_mut(td);
+        let other = *borrow_mut(&other);
 
-        *this = this.abs();
+        this.checked_sub(&other)
+            .map(|td| Shared::new(Locked::new(td.abs())))
+            .ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+    }
+
+    /// Order of two deltas as -1, 0 or 1
+    #[rhai_fn(global, name = "compare", name = "cmp", pure)]
+    pub fn compare(td: &mut Timedelta, rhs: Timedelta) -> rhai::INT {
+        let this = *borrow_mut(td);
+        let rhs = *borrow_mut(&rhs);
+
+        this.cmp(&rhs) as rhai::INT
+    }
+
+    /// Check if two deltas are equal
+    #[rhai_fn(global, name = "==", pure)]
+    pub fn eq(td: &mut Timedelta, rhs: Timedelta) -> bool {
+        compare(td, rhs) == 0
+    }
+
+    /// Check if two deltas differ
+    #[rhai_fn(global, name = "!=", pure)]
+    pub fn ne(td: &mut Timedelta, rhs: Timedelta) -> bool {
+        compare(td, rhs) != 0
+    }
+
+    /// Check if shorter than another delta
+    #[rhai_fn(global, name = "<", pure)]
+    pub fn lt(td: &mut Timedelta, rhs: Timedelta) -> bool {
+        compare(td, rhs) < 0
+    }
+
+    /// Check if shorter than or equal to another delta
+    #[rhai_fn(global, name = "<=", pure)]
+    pub fn le(td: &mut Timedelta, rhs: Timedelta) -> bool {
+        compare(td, rhs) <= 0
+    }
+
+    /// Check if longer than another delta
+    #[rhai_fn(global, name = ">", pure)]
+    pub fn gt(td: &mut Timedelta, rhs: Timedelta) -> bool {
+        compare(td, rhs) > 0
+    }
+
+    /// Check if longer than or equal to another delta
+    #[rhai_fn(global, name = ">=", pure)]
+    pub fn ge(td: &mut Timedelta, rhs: Timedelta) -> bool {
+        compare(td, rhs) >= 0
+    }
+
+    /// Shorter of two deltas
+    #[rhai_fn(global, name = "min", pure)]
+    pub fn min(td: &mut Timedelta, rhs: Timedelta) -> Timedelta {
+        let this = *borrow_mut(td);
+        let rhs = *borrow_mut(&rhs);
+
+        Shared::new(Locked::new(this.min(rhs)))
+    }
+
+    /// Longer of two deltas
+    #[rhai_fn(global, name = "max", pure)]
+    pub fn max(td: &mut Timedelta, rhs: Timedelta) -> Timedelta {
+        let this = *borrow_mut(td);
+        let rhs = *borrow_mut(&rhs);
+
+        Shared::new(Locked::new(this.max(rhs)))
+    }
+
+    /// Delta restricted to the range from `lo` to `hi`
+    #[rhai_fn(global, name = "clamp", pure, return_raw)]
+    pub fn clamp(td: &mut Timedelta, lo: Timedelta, hi: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        let this = *borrow_mut(td);
+        let lo = *borrow_mut(&lo);
+        let hi = *borrow_mut(&hi);
+
+        if lo > hi {
+            return Err(Box::<EvalAltResult>::from(
+                "Lower bound must not be greater than upper bound".to_string(),
+            ));
+        }
+
+        Ok(Shared::new(Locked::new(this.clamp(lo, hi))))
+    }
+
+    /// Check if two deltas are at most `tolerance` apart
+    #[rhai_fn(global, name = "approx_eq", pure)]
+    pub fn approx_eq(td: &mut Timedelta, rhs: Timedelta, tolerance: Timedelta) -> bool {
+        let this = *borrow_mut(td);
+        let rhs = *borrow_mut(&rhs);
+        let tolerance = borrow_mut(&tolerance).abs();
+
+        this.checked_sub(&rhs).is_some_and(|diff| diff.abs() <= tolerance)
+    }
+
+    /// Round to the nearest multiple of a step, ties away from zero
+    #[rhai_fn(global, name = "round", pure, return_raw)]
+    pub fn round(td: &mut Timedelta, step: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        let step = total_nanos(&borrow_mut(&step));
+
+        Ok(Shared::new(Locked::new(round_to(&borrow_mut(td), step, Rounding::HalfUp)?)))
+    }
+
+    /// Round to the nearest multiple of a step, with ties resolved by mode "half_up" or "half_even"
+    #[rhai_fn(global, name = "round", pure, return_raw)]
+    pub fn round_with_mode(td: &mut Timedelta, step: Timedelta, mode: &str) -> Result<Timedelta, Box<EvalAltResult>> {
+        let step = total_nanos(&borrow_mut(&step));
+
+        Ok(Shared::new(Locked::new(round_to(
+            &borrow_mut(td),
+            step,
+            Rounding::from_name(mode)?,
+        )?)))
+    }
+
+    /// Round to the nearest whole unit such as "minute", ties away from zero
+    #[rhai_fn(global, name = "round", pure, return_raw)]
+    pub fn round_unit(td: &mut Timedelta, unit: &str) -> Result<Timedelta, Box<EvalAltResult>> {
+        Ok(Shared::new(Locked::new(round_to(
+            &borrow_mut(td),
+            unit_nanos(unit)?,
+            Rounding::HalfUp,
+        )?)))
+    }
+
+    /// Round to the nearest whole unit such as "minute", with ties resolved by mode "half_up" or "half_even"
+    #[rhai_fn(global, name = "round", pure, return_raw)]
+    pub fn round_unit_with_mode(td: &mut Timedelta, unit: &str, mode: &str) -> Result<Timedelta, Box<EvalAltResult>> {
+        Ok(Shared::new(Locked::new(round_to(
+            &borrow_mut(td),
+            unit_nanos(unit)?,
+            Rounding::from_name(mode)?,
+        )?)))
+    }
+
+    /// Round down to a multiple of a step
+    #[rhai_fn(global, name = "floor", pure, return_raw)]
+    pub fn floor(td: &mut Timedelta, step: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        let step = total_nanos(&borrow_mut(&step));
+
+        Ok(Shared::new(Locked::new(round_to(&borrow_mut(td), step, Rounding::Floor)?)))
+    }
+
+    /// Round down to a whole unit such as "minute"
+    #[rhai_fn(global, name = "floor", pure, return_raw)]
+    pub fn floor_unit(td: &mut Timedelta, unit: &str) -> Result<Timedelta, Box<EvalAltResult>> {
+        Ok(Shared::new(Locked::new(round_to(
+            &borrow_mut(td),
+            unit_nanos(unit)?,
+            Rounding::Floor,
+        )?)))
+    }
+
+    /// Round up to a multiple of a step
+    #[rhai_fn(global, name = "ceil", pure, return_raw)]
+    pub fn ceil(td: &mut Timedelta, step: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        let step = total_nanos(&borrow_mut(&step));
+
+        Ok(Shared::new(Locked::new(round_to(&borrow_mut(td), step, Rounding::Ceil)?)))
+    }
+
+    /// Round up to a whole unit such as "minute"
+    #[rhai_fn(global, name = "ceil", pure, return_raw)]
+    pub fn ceil_unit(td: &mut Timedelta, unit: &str) -> Result<Timedelta, Box<EvalAltResult>> {
+        Ok(Shared::new(Locked::new(round_to(
+            &borrow_mut(td),
+            unit_nanos(unit)?,
+            Rounding::Ceil,
+        )?)))
     }
 
     /// Add two deltas
@@ -159,6 +821,277 @@ pub mod timedelta_module {
         Ok(())
     }
 
+    /// Sum of two deltas
+    #[rhai_fn(global, name = "+", pure, return_raw)]
+    pub fn op_add(td: &mut Timedelta, rhs: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        let this = *borrow_mut(td);
+        let rhs = *borrow_mut(&rhs);
+
+        this.checked_add(&rhs)
+            .map(|td| Shared::new(Locked::new(td)))
+            .ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+    }
+
+    /// Difference of two deltas
+    #[rhai_fn(global, name = "-", pure, return_raw)]
+    pub fn op_sub(td: &mut Timedelta, rhs: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        let this = *borrow_mut(td);
+        let rhs = *borrow_mut(&rhs);
+
+        this.checked_sub(&rhs)
+            .map(|td| Shared::new(Locked::new(td)))
+            .ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+    }
+
+    /// Delta multiplied by an integer
+    #[rhai_fn(global, name = "*", pure, return_raw)]
+    pub fn op_mul(td: &mut Timedelta, factor: rhai::INT) -> Result<Timedelta, Box<EvalAltResult>> {
+        scale(&borrow_mut(td), factor)
+            .map(|td| Shared::new(Locked::new(td)))
+            .ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+    }
+
+    /// Integer multiplied by a delta
+    #[rhai_fn(global, name = "*", return_raw)]
+    pub fn op_mul_rev(factor: rhai::INT, mut td: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        op_mul(&mut td, factor)
+    }
+
+    /// Delta multiplied by a float, rounded to the nanosecond
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "*", pure, return_raw)]
+    pub fn op_mul_float(td: &mut Timedelta, factor: rhai::FLOAT) -> Result<Timedelta, Box<EvalAltResult>> {
+        scale_float(&borrow_mut(td), factor)
+            .map(|td| Shared::new(Locked::new(td)))
+            .ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+    }
+
+    /// Float multiplied by a delta, rounded to the nanosecond
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "*", return_raw)]
+    pub fn op_mul_float_rev(factor: rhai::FLOAT, mut td: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        op_mul_float(&mut td, factor)
+    }
+
+    /// Delta divided by an integer, truncated to the nanosecond
+    #[rhai_fn(global, name = "/", pure, return_raw)]
+    pub fn op_div(td: &mut Timedelta, divisor: rhai::INT) -> Result<Timedelta, Box<EvalAltResult>> {
+        if divisor == 0 {
+            return Err(Box::<EvalAltResult>::from("Division by zero".to_string()));
+        }
+
+        from_total_nanos(total_nanos(&borrow_mut(td)) / divisor as i128)
+            .map(|td| Shared::new(Locked::new(td)))
+            .ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+    }
+
+    /// Delta divided by a float, rounded to the nanosecond
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "/", pure, return_raw)]
+    pub fn op_div_float(td: &mut Timedelta, divisor: rhai::FLOAT) -> Result<Timedelta, Box<EvalAltResult>> {
+        if divisor == 0.0 {
+            return Err(Box::<EvalAltResult>::from("Division by zero".to_string()));
+        }
+
+        divide_float(&borrow_mut(td), divisor)
+            .map(|td| Shared::new(Locked::new(td)))
+            .ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+    }
+
+    /// Ratio of two deltas
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "/", pure, return_raw)]
+    pub fn op_ratio(td: &mut Timedelta, rhs: Timedelta) -> Result<rhai::FLOAT, Box<EvalAltResult>> {
+        let rhs = total_nanos(&borrow_mut(&rhs));
+
+        if rhs == 0 {
+            return Err(Box::<EvalAltResult>::from("Division by zero".to_string()));
+        }
+
+        Ok(total_nanos(&borrow_mut(td)) as rhai::FLOAT / rhs as rhai::FLOAT)
+    }
+
+    /// Remainder of dividing by another delta, carrying the sign of the dividend
+    #[rhai_fn(global, name = "%", pure, return_raw)]
+    pub fn op_rem(td: &mut Timedelta, step: Timedelta) -> Result<Timedelta, Box<EvalAltResult>> {
+        let step = total_nanos(&borrow_mut(&step));
+
+        if step == 0 {
+            return Err(Box::<EvalAltResult>::from("Division by zero".to_string()));
+        }
+
+        from_total_nanos(total_nanos(&borrow_mut(td)) % step)
+            .map(|td| Shared::new(Locked::new(td)))
+            .ok_or(Box::<EvalAltResult>::from("Delta out of range".to_string()))
+    }
+
+    /// Sum of two deltas or unit on overflow
+    #[rhai_fn(global, name = "checked_add", pure)]
+    pub fn checked_add(td: &mut Timedelta, rhs: Timedelta) -> Dynamic {
+        op_add(td, rhs).map_or(Dynamic::UNIT, Dynamic::from)
+    }
+
+    /// Difference of two deltas or unit on overflow
+    #[rhai_fn(global, name = "checked_sub", pure)]
+    pub fn checked_sub(td: &mut Timedelta, rhs: Timedelta) -> Dynamic {
+        op_sub(td, rhs).map_or(Dynamic::UNIT, Dynamic::from)
+    }
+
+    /// Delta multiplied by an integer or unit on overflow
+    #[rhai_fn(global, name = "checked_mul", pure)]
+    pub fn checked_mul(td: &mut Timedelta, factor: rhai::INT) -> Dynamic {
+        op_mul(td, factor).map_or(Dynamic::UNIT, Dynamic::from)
+    }
+
+    /// Delta multiplied by a float or unit on overflow
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "checked_mul", pure)]
+    pub fn checked_mul_float(td: &mut Timedelta, factor: rhai::FLOAT) -> Dynamic {
+        op_mul_float(td, factor).map_or(Dynamic::UNIT, Dynamic::from)
+    }
+
+    /// Delta divided by an integer or unit when dividing by zero
+    #[rhai_fn(global, name = "checked_div", pure)]
+    pub fn checked_div(td: &mut Timedelta, divisor: rhai::INT) -> Dynamic {
+        op_div(td, divisor).map_or(Dynamic::UNIT, Dynamic::from)
+    }
+
+    /// Delta divided by a float or unit when dividing by zero or on overflow
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "checked_div", pure)]
+    pub fn checked_div_float(td: &mut Timedelta, divisor: rhai::FLOAT) -> Dynamic {
+        op_div_float(td, divisor).map_or(Dynamic::UNIT, Dynamic::from)
+    }
+
+    /// Remainder of dividing by another delta or unit when dividing by zero
+    #[rhai_fn(global, name = "checked_rem", pure)]
+    pub fn checked_rem(td: &mut Timedelta, step: Timedelta) -> Dynamic {
+        op_rem(td, step).map_or(Dynamic::UNIT, Dynamic::from)
+    }
+
+    /// Sum of two deltas, clamped to the Timedelta range
+    #[rhai_fn(global, name = "saturating_add", pure)]
+    pub fn saturating_add(td: &mut Timedelta, rhs: Timedelta) -> Timedelta {
+        let negative = *borrow_mut(&rhs) < TimeDelta::zero();
+
+        op_add(td, rhs).unwrap_or_else(|_e| Shared::new(Locked::new(saturate(negative))))
+    }
+
+    /// Difference of two deltas, clamped to the Timedelta range
+    #[rhai_fn(global, name = "saturating_sub", pure)]
+    pub fn saturating_sub(td: &mut Timedelta, rhs: Timedelta) -> Timedelta {
+        let negative = *borrow_mut(&rhs) > TimeDelta::zero();
+
+        op_sub(td, rhs).unwrap_or_else(|_e| Shared::new(Locked::new(saturate(negative))))
+    }
+
+    /// Delta multiplied by an integer, clamped to the Timedelta range
+    #[rhai_fn(global, name = "saturating_mul", pure)]
+    pub fn saturating_mul(td: &mut Timedelta, factor: rhai::INT) -> Timedelta {
+        let negative = (*borrow_mut(td) < TimeDelta::zero()) != (factor < 0);
+
+        op_mul(td, factor).unwrap_or_else(|_e| Shared::new(Locked::new(saturate(negative))))
+    }
+
+    /// Delta multiplied by a float, clamped to the Timedelta range
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, name = "saturating_mul", pure, return_raw)]
+    pub fn saturating_mul_float(td: &mut Timedelta, factor: rhai::FLOAT) -> Result<Timedelta, Box<EvalAltResult>> {
+        let this = *borrow_mut(td);
+        let nanos = total_nanos(&this) as rhai::FLOAT * factor;
+
+        // a NaN factor, or zero times infinity, has no bound to clamp to
+        if nanos.is_nan() {
+            return Err(Box::<EvalAltResult>::from("Delta multiplied by NaN".to_string()));
+        }
+
+        Ok(Shared::new(Locked::new(
+            scale_float(&this, factor).unwrap_or_else(|| saturate(nanos < 0.0)),
+        )))
+    }
+
+    /// Output duration with a pattern such as "%H:%M:%S%.3f"
+    #[rhai_fn(global, name = "format", pure, return_raw)]
+    pub fn format(td: &mut Timedelta, pattern: &str) -> Result<ImmutableString, Box<EvalAltResult>> {
+        format_pattern(&borrow_mut(td), pattern).map(Into::into)
+    }
+
+    /// Output compact duration such as "1h 30m"
+    #[rhai_fn(global, name = "format_compact", pure)]
+    pub fn to_compact(td: &mut Timedelta) -> ImmutableString {
+        format_compact(&borrow_mut(td)).into()
+    }
+
+    /// Output spelled-out duration such as "1 hour, 30 minutes"
+    #[rhai_fn(global, name = "format_verbose", pure, return_raw)]
+    pub fn to_verbose(td: &mut Timedelta) -> Result<ImmutableString, Box<EvalAltResult>> {
+        to_verbose_with_words(td, rhai::INT::MAX, rhai::Map::new())
+    }
+
+    /// Output spelled-out duration with at most the given number of units
+    #[rhai_fn(global, name = "format_verbose", pure, return_raw)]
+    pub fn to_verbose_max_units(td: &mut Timedelta, max_units: rhai::INT) -> Result<ImmutableString, Box<EvalAltResult>> {
+        to_verbose_with_words(td, max_units, rhai::Map::new())
+    }
+
+    /// Output spelled-out duration with at most the given number of units, using unit names and separators from a map
+    #[rhai_fn(global, name = "format_verbose", pure, return_raw)]
+    pub fn to_verbose_with_words(
+        td: &mut Timedelta,
+        max_units: rhai::INT,
+        words: rhai::Map,
+    ) -> Result<ImmutableString, Box<EvalAltResult>> {
+        format_verbose(&borrow_mut(td), max_units, &words).map(Into::into)
+    }
+
+    /// Get the fractional number of seconds
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, get = "total_seconds_f", name = "total_seconds_f", pure)]
+    pub fn total_seconds_f(td: &mut Timedelta) -> rhai::FLOAT {
+        total_nanos(&borrow_mut(td)) as rhai::FLOAT / 1e9
+    }
+
+    /// Get the fractional number of minutes
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, get = "total_minutes_f", name = "total_minutes_f", pure)]
+    pub fn total_minutes_f(td: &mut Timedelta) -> rhai::FLOAT {
+        total_nanos(&borrow_mut(td)) as rhai::FLOAT / 60e9
+    }
+
+    /// Get the fractional number of hours
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, get = "total_hours_f", name = "total_hours_f", pure)]
+    pub fn total_hours_f(td: &mut Timedelta) -> rhai::FLOAT {
+        total_nanos(&borrow_mut(td)) as rhai::FLOAT / 3600e9
+    }
+
+    /// Get the fractional number of days
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(global, get = "total_days_f", name = "total_days_f", pure)]
+    pub fn total_days_f(td: &mut Timedelta) -> rhai::FLOAT {
+        total_nanos(&borrow_mut(td)) as rhai::FLOAT / 86400e9
+    }
+
+    /// Split into normalized days, hours, minutes, seconds, millis and nanos of the absolute value, plus the sign
+    #[rhai_fn(global, get = "components", name = "components", pure)]
+    pub fn components(td: &mut Timedelta) -> rhai::Map {
+        let total = total_nanos(&borrow_mut(td));
+        let nanos = total.abs();
+        let seconds = nanos / 1_000_000_000;
+
+        let mut map = rhai::Map::new();
+
+        map.insert("days".into(), Dynamic::from_int((seconds / 86_400) as rhai::INT));
+        map.insert("hours".into(), Dynamic::from_int((seconds / 3_600 % 24) as rhai::INT));
+        map.insert("minutes".into(), Dynamic::from_int((seconds / 60 % 60) as rhai::INT));
+        map.insert("seconds".into(), Dynamic::from_int((seconds % 60) as rhai::INT));
+        map.insert("millis".into(), Dynamic::from_int((nanos / 1_000_000 % 1_000) as rhai::INT));
+        map.insert("nanos".into(), Dynamic::from_int((nanos % 1_000_000) as rhai::INT));
+        map.insert("sign".into(), Dynamic::from_int(total.signum() as rhai::INT));
+
+        map
+    }
+
     /// Get the seconds delta
     #[rhai_fn(global, get = "seconds", name = "seconds", name = "get_seconds", pure)]
     pub fn get_seconds(td: &mut Timedelta) -> rhai::INT {